@@ -0,0 +1,57 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use parse_dat_url::DatUrl;
+use regex::Regex;
+use std::hint::black_box;
+use url::Url;
+
+const URLS: &[&str] = &[
+    "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21",
+    "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21+0.0.0.1/path/to+file.txt",
+    "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21+latest/",
+    "dat://example.com+v1.0.0/path/to+file.txt?x=1#top",
+    "example.com/path/to+file.txt",
+    "[2001:DB8::0]+0.0.0.1/path/to+file.txt",
+];
+
+/// The previous implementation: regex matching, followed by formatting a new string for the `url` parser.
+fn regex_parse(regex: &Regex, url: &str) -> Option<Url> {
+    let capture = regex.captures(url)?;
+    let scheme = capture.name("scheme").map_or("dat://", |c| c.as_str());
+    let host = capture.name("hostname")?.as_str();
+    let path = capture.name("path").map_or("", |c| c.as_str());
+    Url::parse(&format!("{}{}{}", scheme, host, path)).ok()
+}
+
+fn bench_parse(c: &mut Criterion) {
+    let regex = Regex::new(
+        r#"(?i)^(?P<scheme>\w+://)?(?P<hostname>[^/+]+)(\+(?P<version>[^/]+))?(?P<path>.*)$"#,
+    )
+    .expect("Version regex not valid");
+
+    let mut group = c.benchmark_group("parse");
+    group.bench_function("DatUrl::parse", |b| {
+        b.iter(|| {
+            for url in URLS {
+                black_box(DatUrl::parse(black_box(url)).ok());
+            }
+        })
+    });
+    group.bench_function("regex", |b| {
+        b.iter(|| {
+            for url in URLS {
+                black_box(regex_parse(&regex, black_box(url)));
+            }
+        })
+    });
+    group.bench_function("Url::parse", |b| {
+        b.iter(|| {
+            for url in URLS {
+                black_box(Url::parse(black_box(url)).ok());
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_parse);
+criterion_main!(benches);
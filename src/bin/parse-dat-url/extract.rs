@@ -0,0 +1,55 @@
+use crate::files::{read_text, Files};
+use crate::inspect;
+use parse_dat_url::find_iter;
+use std::io::{self, Write};
+use std::path::Path;
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    #[command(flatten)]
+    files: Files,
+    /// Print one JSON object per link instead of plain text.
+    #[arg(long)]
+    json: bool,
+}
+
+/// Prints every link found, returning `false` if any file could not be read.
+pub fn run(args: &Args) -> io::Result<bool> {
+    let (paths, mut success) = args.files.collect()?;
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    for path in &paths {
+        match read_text(path) {
+            Ok(Some(text)) => search(&mut out, path, &text, args.json)?,
+            Ok(None) => {}
+            Err(error) => {
+                eprintln!("error: {}: {}", path.display(), error);
+                success = false;
+            }
+        }
+    }
+    Ok(success)
+}
+
+fn search(out: &mut impl Write, path: &Path, text: &str, json: bool) -> io::Result<()> {
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(text.match_indices('\n').map(|(index, _)| index + 1))
+        .collect();
+    for (range, dat_url) in find_iter(text) {
+        let line = line_starts.partition_point(|start| *start <= range.start);
+        let column = range.start - line_starts[line - 1] + 1;
+
+        if json {
+            let mut value = inspect::to_json(&dat_url);
+            value["file"] = path.display().to_string().into();
+            value["line"] = line.into();
+            value["column"] = column.into();
+            value["text"] = text[range].into();
+            writeln!(out, "{}", value)?;
+        } else {
+            writeln!(out, "{}:{}:{}: {}", path.display(), line, column, dat_url)?;
+        }
+    }
+    Ok(())
+}
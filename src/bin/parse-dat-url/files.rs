@@ -0,0 +1,103 @@
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+use walkdir::WalkDir;
+
+#[derive(Debug, clap::Args)]
+pub struct Files {
+    /// Files and directories to process. Directories are walked recursively. Defaults to the current directory.
+    paths: Vec<PathBuf>,
+    /// Only process files matching the glob, relative to the directory walked, such as `*.md`. Can be repeated.
+    #[arg(long, value_name = "GLOB")]
+    include: Vec<String>,
+    /// Skip files and directories matching the glob, relative to the directory walked, such as `target`. Can be repeated.
+    #[arg(long, value_name = "GLOB")]
+    exclude: Vec<String>,
+}
+
+impl Files {
+    /// Returns the files to process, and `false` if a directory could not be walked entirely.
+    ///
+    /// Files given explicitly are always returned, while files found by walking directories are filtered by the globs.
+    pub fn collect(&self) -> io::Result<(Vec<PathBuf>, bool)> {
+        let include = glob_set(&self.include)?;
+        let exclude = glob_set(&self.exclude)?;
+        let roots = if self.paths.is_empty() {
+            vec![PathBuf::from(".")]
+        } else {
+            self.paths.clone()
+        };
+
+        let mut files = Vec::new();
+        let mut success = true;
+        for root in &roots {
+            if !root.is_dir() {
+                files.push(root.clone());
+                continue;
+            }
+
+            let entries = WalkDir::new(root)
+                .sort_by_file_name()
+                .into_iter()
+                .filter_entry(|entry| {
+                    let relative = entry.path().strip_prefix(root).unwrap_or(entry.path());
+                    relative.as_os_str().is_empty() || !exclude.is_match(relative)
+                });
+            for entry in entries {
+                let entry = match entry {
+                    Ok(entry) => entry,
+                    Err(error) => {
+                        eprintln!("error: {}", error);
+                        success = false;
+                        continue;
+                    }
+                };
+                let relative = entry.path().strip_prefix(root).unwrap_or(entry.path());
+                if entry.file_type().is_file() && (include.is_empty() || include.is_match(relative))
+                {
+                    files.push(entry.into_path());
+                }
+            }
+        }
+        Ok((files, success))
+    }
+}
+
+/// Reads the file as text, returning `None` if it is not valid UTF-8, such as binary files.
+pub fn read_text(path: &Path) -> io::Result<Option<String>> {
+    Ok(String::from_utf8(fs::read(path)?).ok())
+}
+
+/// Writes the text next to the file first, and then renames it over the file, so readers never see a partially pinned file.
+///
+/// The permissions of the file are kept, and the temporary file is removed if the write fails.
+pub fn write_text(path: &Path, text: &str) -> io::Result<()> {
+    let mut name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no file name"))?
+        .to_os_string();
+    name.push(format!(".{}.tmp", process::id()));
+    let temporary = path.with_file_name(name);
+
+    let written = fs::write(&temporary, text)
+        .and_then(|()| fs::set_permissions(&temporary, fs::metadata(path)?.permissions()))
+        .and_then(|()| fs::rename(&temporary, path));
+    if written.is_err() {
+        let _ = fs::remove_file(&temporary);
+    }
+    written
+}
+
+fn glob_set(globs: &[String]) -> io::Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for glob in globs {
+        let glob =
+            Glob::new(glob).map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+        builder.add(glob);
+    }
+    builder
+        .build()
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))
+}
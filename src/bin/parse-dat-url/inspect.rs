@@ -0,0 +1,133 @@
+use parse_dat_url::{DatHost, DatUrl, Version};
+use serde_json::{json, Value};
+use std::io::{self, BufRead, Write};
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// Urls to inspect. Read from stdin, one per line, when none is given.
+    urls: Vec<String>,
+    /// Print one JSON object per url instead of a table.
+    #[arg(long)]
+    json: bool,
+}
+
+/// Prints each url, returning `false` if any of them failed to parse.
+pub fn run(args: &Args) -> io::Result<bool> {
+    let inputs = if args.urls.is_empty() {
+        io::stdin().lock().lines().collect::<io::Result<Vec<_>>>()?
+    } else {
+        args.urls.clone()
+    };
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    let mut success = true;
+    let mut first = true;
+    for input in inputs.iter().map(|input| input.trim()) {
+        if input.is_empty() {
+            continue;
+        }
+        match DatUrl::parse(input) {
+            Ok(dat_url) if args.json => writeln!(out, "{}", to_json(&dat_url))?,
+            Ok(dat_url) => {
+                if !first {
+                    writeln!(out)?;
+                }
+                first = false;
+                write_table(&mut out, &dat_url)?;
+            }
+            Err(error) => {
+                success = false;
+                if args.json {
+                    let value = json!({
+                        "input": input,
+                        "error": format!("{:?}", error),
+                        "message": error.to_string(),
+                    });
+                    writeln!(out, "{}", value)?;
+                } else {
+                    eprintln!("error: {}: {:?} ({})", input, error, error);
+                }
+            }
+        }
+    }
+    Ok(success)
+}
+
+fn host_kind(dat_url: &DatUrl<'_>) -> &'static str {
+    match dat_url.dat_host() {
+        DatHost::Key(..) => "key",
+        DatHost::Domain(..) => "domain",
+        DatHost::Ipv4(..) => "ipv4",
+        DatHost::Ipv6(..) => "ipv6",
+    }
+}
+
+fn version_kind(version: &Version<'_>) -> &'static str {
+    match version {
+        Version::Number(_) => "number",
+        Version::Content(_) => "content",
+        Version::Tag(_) => "tag",
+        Version::Latest => "latest",
+        Version::Preview => "preview",
+    }
+}
+
+pub fn to_json(dat_url: &DatUrl<'_>) -> Value {
+    json!({
+        "url": dat_url.to_string(),
+        "scheme": dat_url.scheme().trim_end_matches("://"),
+        "host": dat_url.host(),
+        "host_kind": host_kind(dat_url),
+        "key": dat_url.key().map(|key| key.to_hex()),
+        "version": dat_url.version().as_ref().map(ToString::to_string),
+        "version_kind": dat_url.version().as_ref().map(version_kind),
+        "path": dat_url.path(),
+        "query": dat_url.query(),
+        "fragment": dat_url.fragment(),
+    })
+}
+
+fn write_table(out: &mut impl Write, dat_url: &DatUrl<'_>) -> io::Result<()> {
+    let or_dash = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+    let rows = [
+        ("url", dat_url.to_string()),
+        (
+            "scheme",
+            dat_url.scheme().trim_end_matches("://").to_string(),
+        ),
+        ("host", dat_url.host().to_string()),
+        ("host kind", host_kind(dat_url).to_string()),
+        ("key", or_dash(dat_url.key().map(|key| key.to_hex()))),
+        (
+            "version",
+            or_dash(
+                dat_url
+                    .version()
+                    .as_ref()
+                    .map(|version| format!("{} ({})", version, version_kind(version))),
+            ),
+        ),
+        (
+            "path",
+            or_dash(dat_url.path().as_ref().map(|path| path.to_string())),
+        ),
+        (
+            "query",
+            or_dash(dat_url.query().as_ref().map(|query| query.to_string())),
+        ),
+        (
+            "fragment",
+            or_dash(
+                dat_url
+                    .fragment()
+                    .as_ref()
+                    .map(|fragment| fragment.to_string()),
+            ),
+        ),
+    ];
+    for (label, value) in &rows {
+        writeln!(out, "{:<10}{}", label, value)?;
+    }
+    Ok(())
+}
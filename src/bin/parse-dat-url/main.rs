@@ -0,0 +1,51 @@
+//! Command-line inspector for dat urls, showing how `parse-dat-url` breaks them apart,
+//! finding dat links across files, and pinning them to versions.
+
+use clap::{Parser, Subcommand};
+use std::io;
+use std::process::ExitCode;
+
+mod extract;
+mod files;
+mod inspect;
+mod pin;
+
+/// Inspect how dat urls are parsed.
+#[derive(Debug, Parser)]
+#[command(
+    name = "parse-dat-url",
+    version,
+    args_conflicts_with_subcommands = true
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+    #[command(flatten)]
+    inspect: inspect::Args,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Find dat and hyper links in files, printing file:line:column and the parsed url.
+    Extract(extract::Args),
+    /// Pin unversioned links to the versions listed for their keys, rewriting the files in place.
+    Pin(pin::Args),
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = match &cli.command {
+        Some(Command::Extract(args)) => extract::run(args),
+        Some(Command::Pin(args)) => pin::run(args),
+        None => inspect::run(&cli.inspect),
+    };
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(error) if error.kind() == io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use crate::files::{read_text, write_text, Files};
+use parse_dat_url::{apply_pins, pin_versions, DatKey, DatUrl, Pin, Version};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    #[command(flatten)]
+    files: Files,
+    /// File mapping keys to versions, one `<key> <version>` pair per line. Text after a `#` is a comment.
+    #[arg(long, value_name = "FILE")]
+    versions: PathBuf,
+    /// Print the changes as a diff instead of rewriting the files.
+    #[arg(long)]
+    dry_run: bool,
+}
+
+/// Pins the unversioned links of each file, returning `false` if any file could not be read or written.
+pub fn run(args: &Args) -> io::Result<bool> {
+    let versions = parse_versions(&fs::read_to_string(&args.versions)?)?;
+    let (paths, mut success) = args.files.collect()?;
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    for path in &paths {
+        let text = match read_text(path) {
+            Ok(Some(text)) => text,
+            Ok(None) => continue,
+            Err(error) => {
+                eprintln!("error: {}: {}", path.display(), error);
+                success = false;
+                continue;
+            }
+        };
+
+        let pins = pin_versions(&text, |key| versions.get(key).cloned());
+        if pins.is_empty() {
+            continue;
+        }
+        if args.dry_run {
+            write_diff(&mut out, path, &text, &pins)?;
+        } else if let Err(error) = write_text(path, &apply_pins(&text, &pins)) {
+            eprintln!("error: {}: {}", path.display(), error);
+            success = false;
+        } else {
+            writeln!(out, "{}: {} links pinned", path.display(), pins.len())?;
+        }
+    }
+    Ok(success)
+}
+
+/// Parses the versions file, failing with [io::ErrorKind::InvalidData] on malformed lines, versions which do not round trip on a url,
+/// or keys mapped to several versions.
+fn parse_versions(text: &str) -> io::Result<HashMap<DatKey, Version<'static>>> {
+    let invalid = |index: usize, message: &str| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("line {}: {}", index + 1, message),
+        )
+    };
+
+    let mut versions = HashMap::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default();
+        let fields: Vec<_> = line.split_whitespace().collect();
+        let (key, version) = match fields[..] {
+            [] => continue,
+            [key, version] => (key, version),
+            _ => return Err(invalid(index, "expected a key and a version")),
+        };
+
+        let key = DatKey::from_hex(key)
+            .ok()
+            .or_else(|| DatUrl::options().require_key(true).parse(key).ok()?.key())
+            .ok_or_else(|| invalid(index, "invalid key"))?;
+        let version: Version<'static> = version
+            .parse()
+            .ok()
+            .filter(Version::round_trips)
+            .ok_or_else(|| invalid(index, "invalid version"))?;
+        if versions.get(&key).is_some_and(|known| *known != version) {
+            return Err(invalid(index, "key mapped to several versions"));
+        }
+        versions.insert(key, version);
+    }
+    Ok(versions)
+}
+
+/// Writes the lines changed by the pins as a unified diff, with one hunk per line and no context lines.
+fn write_diff(out: &mut impl Write, path: &Path, text: &str, pins: &[Pin<'_>]) -> io::Result<()> {
+    writeln!(out, "--- {}", path.display())?;
+    writeln!(out, "+++ {}", path.display())?;
+
+    let mut remaining = pins;
+    while let Some(first) = remaining.first() {
+        let start = text[..first.range.start]
+            .rfind('\n')
+            .map_or(0, |index| index + 1);
+        let end = text[first.range.end..]
+            .find('\n')
+            .map_or(text.len(), |index| first.range.end + index);
+        let count = remaining
+            .iter()
+            .take_while(|pin| pin.range.start < end)
+            .count();
+        let (on_line, rest) = remaining.split_at(count);
+
+        let line = &text[start..end];
+        let shifted: Vec<_> = on_line
+            .iter()
+            .map(|pin| Pin {
+                range: pin.range.start - start..pin.range.end - start,
+                original: pin.original,
+                pinned: pin.pinned.clone(),
+            })
+            .collect();
+        let number = text[..start].matches('\n').count() + 1;
+        let no_newline = if end == text.len() && !text.ends_with('\n') {
+            "\n\\ No newline at end of file"
+        } else {
+            ""
+        };
+        writeln!(out, "@@ -{0},1 +{0},1 @@", number)?;
+        writeln!(out, "-{}{}", line.trim_end_matches('\r'), no_newline)?;
+        writeln!(
+            out,
+            "+{}{}",
+            apply_pins(line, &shifted).trim_end_matches('\r'),
+            no_newline
+        )?;
+        remaining = rest;
+    }
+    Ok(())
+}
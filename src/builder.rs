@@ -0,0 +1,222 @@
+use crate::{DatKey, DatUrl, Error, Version, DAT_SCHEME, HYPER_SCHEME};
+use alloc::borrow::ToOwned;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+use url::form_urlencoded;
+use url::Url;
+
+/// Characters percent-encoded on a path segment, following the [URL Standard](https://url.spec.whatwg.org/#path-percent-encode-set).
+const PATH_SEGMENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}')
+    .add(b'/')
+    .add(b'%');
+
+/// Characters percent-encoded on the fragment, following the [URL Standard](https://url.spec.whatwg.org/#fragment-percent-encode-set).
+/// `%` is encoded as well, so the fragment is taken as text rather than as percent-encoded data, as done for path segments.
+const FRAGMENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'<')
+    .add(b'>')
+    .add(b'`')
+    .add(b'%');
+
+/// Builder to construct a [DatUrl](crate::DatUrl) programmatically, without formatting and parsing a string.
+///
+/// Each part is checked as it is set, and path segments, query pairs and the fragment are percent-encoded automatically.
+///
+/// # Example
+///
+/// ```rust
+/// use parse_dat_url::{DatKey, DatUrl, Version};
+///
+/// let key: DatKey = "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".parse()?;
+/// let dat_url = DatUrl::builder()
+///     .key(key)
+///     .version(Version::Number(5))?
+///     .path_segments(["docs", "read me.md"])
+///     .query_pair("lang", "en")
+///     .fragment("usage")
+///     .build()?;
+///
+/// assert_eq!(
+///     "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21+5/docs/read%20me.md?lang=en#usage",
+///     dat_url.to_string()
+/// );
+/// # Ok::<(), parse_dat_url::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct DatUrlBuilder {
+    scheme: &'static str,
+    host: Option<String>,
+    port: Option<u16>,
+    version: Option<Version<'static>>,
+    path: Option<String>,
+    query: Vec<(String, String)>,
+    fragment: Option<String>,
+}
+
+impl Default for DatUrlBuilder {
+    fn default() -> Self {
+        DatUrlBuilder {
+            scheme: DAT_SCHEME,
+            host: None,
+            port: None,
+            version: None,
+            path: None,
+            query: Vec::new(),
+            fragment: None,
+        }
+    }
+}
+
+impl DatUrlBuilder {
+    /// Creates an empty builder, using the `dat://` scheme.
+    #[inline]
+    pub fn new() -> Self {
+        DatUrlBuilder::default()
+    }
+
+    /// Sets the scheme, either `dat` or `hyper`, in any case and with an optional trailing `://`.
+    ///
+    /// Fails with [Error::UnsupportedScheme](crate::Error::UnsupportedScheme) for other schemes,
+    /// and with [Error::InvalidVersion](crate::Error::InvalidVersion) if the version set is not supported by `hyper://` urls.
+    pub fn scheme(mut self, scheme: &str) -> Result<Self, Error> {
+        let scheme = scheme.trim_end_matches("://");
+        self.scheme = if scheme.eq_ignore_ascii_case("dat") {
+            DAT_SCHEME
+        } else if scheme.eq_ignore_ascii_case("hyper") {
+            HYPER_SCHEME
+        } else {
+            return Err(Error::UnsupportedScheme);
+        };
+        self.check_version()?;
+        Ok(self)
+    }
+
+    /// Sets the host to the archive key.
+    pub fn key(mut self, key: DatKey) -> Self {
+        self.host = Some(key.to_hex());
+        self
+    }
+
+    /// Sets the host to a domain name, which must be resolved into a key before fetching the archive, or to an IP address.
+    ///
+    /// The host is kept as written, the way [DatUrl::parse](crate::DatUrl::parse) reads it, and may end with a `:port`.
+    ///
+    /// Fails with [Error::InvalidUrl](crate::Error::InvalidUrl) if the host is not a valid opaque host according to the URL Standard, or contains one of `/`, `+`, `?` and `#`,
+    /// and with [Error::MissingHostname](crate::Error::MissingHostname) if it is empty.
+    pub fn domain(mut self, domain: &str) -> Result<Self, Error> {
+        DatUrl::check_host(domain)?;
+        self.host = Some(domain.to_owned());
+        Ok(self)
+    }
+
+    /// Sets the port, written after the key or domain.
+    pub fn port(mut self, port: u16) -> Self {
+        self.port = Some(port);
+        self
+    }
+
+    /// Sets the version of the archive.
+    ///
+    /// Fails with [Error::InvalidVersion](crate::Error::InvalidVersion) if the version is not supported by the scheme set,
+    /// or if it would be read back as another version, such as `Version::Tag("1x")` or a tag containing a `/`.
+    pub fn version(mut self, version: Version<'_>) -> Result<Self, Error> {
+        self.version = Some(version.into_owned());
+        self.check_version()?;
+        Ok(self)
+    }
+
+    /// Appends a segment to the path, percent-encoding it. A `/` inside the segment is encoded as `%2F`.
+    pub fn path_segment(mut self, segment: &str) -> Self {
+        let path = self.path.get_or_insert_with(String::new);
+        path.push('/');
+        path.extend(utf8_percent_encode(segment, PATH_SEGMENT));
+        self
+    }
+
+    /// Appends each segment to the path, percent-encoding them. See [path_segment](#method.path_segment).
+    pub fn path_segments<I, S>(self, segments: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        segments.into_iter().fold(self, |builder, segment| {
+            builder.path_segment(segment.as_ref())
+        })
+    }
+
+    /// Appends a pair to the query string, encoded as `application/x-www-form-urlencoded`.
+    pub fn query_pair(mut self, key: &str, value: &str) -> Self {
+        self.query.push((key.to_owned(), value.to_owned()));
+        self
+    }
+
+    /// Sets the fragment, percent-encoding it.
+    pub fn fragment(mut self, fragment: &str) -> Self {
+        self.fragment = Some(utf8_percent_encode(fragment, FRAGMENT).collect());
+        self
+    }
+
+    /// Builds the [DatUrl](crate::DatUrl).
+    ///
+    /// Fails with [Error::MissingHostname](crate::Error::MissingHostname) if neither a key nor a domain was set,
+    /// and with [Error::InvalidUrl](crate::Error::InvalidUrl) if a port was set on a domain already holding one.
+    pub fn build(self) -> Result<DatUrl<'static>, Error> {
+        let host = self.host.ok_or(Error::MissingHostname)?;
+        let host = match self.port {
+            Some(port) => format!("{}:{}", host, port),
+            None => host,
+        };
+        let query = if self.query.is_empty() {
+            None
+        } else {
+            Some(
+                form_urlencoded::Serializer::new(String::new())
+                    .extend_pairs(&self.query)
+                    .finish(),
+            )
+        };
+
+        let url = Url::parse(&DatUrl::url_str(
+            self.scheme,
+            &host,
+            self.path.as_deref(),
+            query.as_deref(),
+            self.fragment.as_deref(),
+        ))
+        .map_err(Error::InvalidUrl)?;
+
+        Ok(DatUrl {
+            scheme: self.scheme.into(),
+            host: host.into(),
+            version: self.version,
+            path: self.path.map(Into::into),
+            query: query.map(Into::into),
+            fragment: self.fragment.map(Into::into),
+            url,
+        })
+    }
+
+    fn check_version(&self) -> Result<(), Error> {
+        let valid = self.version.as_ref().is_none_or(|version| {
+            version.round_trips() && (self.scheme != HYPER_SCHEME || version.is_hyper_compatible())
+        });
+        if valid {
+            Ok(())
+        } else {
+            Err(Error::InvalidVersion)
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use crate::{parser, DatUrl, ParseOptions};
+use core::ops::Range;
+
+const SCHEMES: &[&str] = &["dat://", "hyper://"];
+const KEY_LEN: usize = 64;
+
+/// Finds every `dat://` and `hyper://` url, and every bare 64 characters hexadecimal key, in free-form text.
+///
+/// Returns an iterator of the byte range of each match in the text, alongside the parsed [DatUrl](crate::DatUrl).
+/// Candidates which fail to parse are skipped.
+///
+/// A match ends on whitespace or on one of `<`, `>`, `"` and `` ` ``. Then, as done by GitHub Flavored Markdown autolinks:
+///
+/// - Trailing punctuation, such as `.`, `,`, `:`, `;`, `!`, `?`, `'`, `*`, `_` and `~`, is left out of the match.
+/// - A trailing `)` is left out of the match unless it closes a `(` inside the match, so `(see dat://…/a)` and `[text](dat://…)` work.
+/// - A `]` ends the match unless it closes a `[` inside the match, such as an IPv6 host, so `[dat://…](…)` works.
+///
+/// Bare keys must not be preceded by letters, digits or url characters such as `/`, so keys which are part of other urls are not matched.
+///
+/// # Example
+///
+/// ```rust
+/// use parse_dat_url::find_iter;
+///
+/// let text = "Mirror at (dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21+5/index.html).";
+/// let found: Vec<_> = find_iter(text).collect();
+///
+/// assert_eq!(1, found.len());
+/// let (range, dat_url) = &found[0];
+/// assert_eq!(&text[range.clone()], dat_url.to_string());
+/// assert_eq!(&Some("/index.html".into()), dat_url.path());
+/// ```
+pub fn find_iter(text: &str) -> FindIter<'_> {
+    FindIter { text, position: 0 }
+}
+
+/// Iterator over the dat urls found in free-form text, created with [find_iter](crate::find_iter).
+#[derive(Debug, Clone)]
+pub struct FindIter<'t> {
+    text: &'t str,
+    position: usize,
+}
+
+impl<'t> Iterator for FindIter<'t> {
+    type Item = (Range<usize>, DatUrl<'t>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let text = self.text;
+        while self.position < text.len() {
+            let start = self.position;
+            let previous = text[..start].chars().next_back();
+            let current = text[start..].chars().next()?;
+
+            let found = if current.is_ascii_alphabetic() && !previous.is_some_and(is_word) {
+                match_scheme(text, start)
+            } else {
+                None
+            };
+            let found = found.or_else(|| {
+                if current.is_ascii_hexdigit() && previous.is_none_or(is_key_boundary) {
+                    match_key(text, start)
+                } else {
+                    None
+                }
+            });
+
+            match found {
+                Some((range, dat_url)) => {
+                    self.position = range.end;
+                    return Some((range, dat_url));
+                }
+                None => self.position += current.len_utf8(),
+            }
+        }
+        None
+    }
+}
+
+fn match_scheme(text: &str, start: usize) -> Option<(Range<usize>, DatUrl<'_>)> {
+    let rest = &text[start..];
+    SCHEMES.iter().find(|scheme| {
+        rest.get(..scheme.len())
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case(scheme))
+    })?;
+
+    let end = start + candidate_len(rest);
+    // Without a host after the scheme, the grammar would read the scheme itself as the host.
+    parser::split(&text[start..end])?.scheme?;
+    let options = DatUrl::options().allowed_schemes(&["dat", "hyper"]);
+    parse_candidate(text, start..end, &options)
+}
+
+fn match_key(text: &str, start: usize) -> Option<(Range<usize>, DatUrl<'_>)> {
+    let rest = &text[start..];
+    let key = rest.get(..KEY_LEN)?;
+    if !key.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    if rest[KEY_LEN..].chars().next().is_some_and(is_word) {
+        return None;
+    }
+
+    let end = start + candidate_len(rest);
+    let options = DatUrl::options().require_key(true);
+    parse_candidate(text, start..end, &options)
+}
+
+fn parse_candidate<'t>(
+    text: &'t str,
+    range: Range<usize>,
+    options: &ParseOptions<'_>,
+) -> Option<(Range<usize>, DatUrl<'t>)> {
+    options
+        .parse(&text[range.clone()])
+        .ok()
+        .map(|dat_url| (range, dat_url))
+}
+
+/// Length of the candidate url at the start of the text, after leaving trailing punctuation and unbalanced brackets out.
+fn candidate_len(text: &str) -> usize {
+    let mut open_squares = 0usize;
+    let mut end = text.len();
+    for (index, c) in text.char_indices() {
+        match c {
+            c if c.is_whitespace() || c.is_control() => {
+                end = index;
+                break;
+            }
+            '<' | '>' | '"' | '`' => {
+                end = index;
+                break;
+            }
+            '[' => open_squares += 1,
+            ']' if open_squares == 0 => {
+                end = index;
+                break;
+            }
+            ']' => open_squares -= 1,
+            _ => {}
+        }
+    }
+
+    let mut candidate = &text[..end];
+    loop {
+        let trimmed = match candidate.chars().next_back() {
+            Some('.' | ',' | ':' | ';' | '!' | '?' | '\'' | '*' | '_' | '~') => {
+                &candidate[..candidate.len() - 1]
+            }
+            Some(')') if candidate.matches('(').count() < candidate.matches(')').count() => {
+                &candidate[..candidate.len() - 1]
+            }
+            _ => break,
+        };
+        candidate = trimmed;
+    }
+    candidate.len()
+}
+
+fn is_word(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn is_key_boundary(c: char) -> bool {
+    c.is_whitespace() || "([{<\"'`*_~,;".contains(c)
+}
@@ -0,0 +1,202 @@
+use crate::{DatHost, DatKey, DatUrl, Error, DAT_SCHEME, HYPER_SCHEME};
+use alloc::borrow::ToOwned;
+use alloc::format;
+use alloc::string::ToString;
+use url::{Host, Url};
+
+/// How a dat-to-HTTP gateway lays out archives on its urls.
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub enum GatewayLayout {
+    /// Archives are served under the gateway path, as `http://gateway/<host>+<version>/path`.
+    Path,
+    /// Archives are served on a subdomain of the gateway, as `https://<base32 key>.gateway.tld/+<version>/path`.
+    ///
+    /// The key is encoded with [DatKey::to_base32](crate::DatKey::to_base32) to fit on a DNS label.
+    /// Urls which cannot be expressed as a subdomain, such as domain hosts or keys with a port, fall back to the path layout on the gateway host itself.
+    Subdomain,
+}
+
+/// Configuration of a dat-to-HTTP gateway, used by [DatUrl::to_gateway_url](crate::DatUrl::to_gateway_url) and [DatUrl::from_gateway_url](crate::DatUrl::from_gateway_url).
+///
+/// The base url sets the scheme, host, port and path prefix of the gateway. Its query and fragment are ignored.
+/// Gateway urls do not carry the dat scheme, so urls mapped back from the gateway use the scheme of the configuration, `dat://` by default.
+///
+/// # Example
+///
+/// ```rust
+/// use parse_dat_url::{DatUrl, GatewayConfig};
+///
+/// let config = GatewayConfig::subdomain("https://gateway.tld")?;
+/// let dat_url = DatUrl::parse("dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21+5/index.html")?;
+///
+/// let url = dat_url.to_gateway_url(&config);
+/// assert_eq!(
+///     "https://lbh2ubotsqmqvmnd6asamb7zx4vx4k6zs2edbii46563btvdniqq.gateway.tld/+5/index.html",
+///     url.as_str()
+/// );
+/// assert_eq!(dat_url, DatUrl::from_gateway_url(&url, &config)?);
+/// # Ok::<(), parse_dat_url::Error>(())
+/// ```
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct GatewayConfig {
+    base: Url,
+    layout: GatewayLayout,
+    scheme: &'static str,
+}
+
+impl GatewayConfig {
+    /// Creates the configuration of a gateway serving archives under its path, such as `http://gateway/`.
+    ///
+    /// Fails with [Error::InvalidUrl](crate::Error::InvalidUrl) if the base is not a valid url,
+    /// and with [Error::MissingHostname](crate::Error::MissingHostname) if it has no host.
+    pub fn path(base: &str) -> Result<GatewayConfig, Error> {
+        GatewayConfig::new(base, GatewayLayout::Path)
+    }
+
+    /// Creates the configuration of a gateway serving archives on its subdomains, such as `https://gateway.tld`.
+    ///
+    /// Fails with [Error::InvalidUrl](crate::Error::InvalidUrl) if the base is not a valid url,
+    /// and with [Error::MissingHostname](crate::Error::MissingHostname) if its host is not a domain name.
+    pub fn subdomain(base: &str) -> Result<GatewayConfig, Error> {
+        GatewayConfig::new(base, GatewayLayout::Subdomain)
+    }
+
+    fn new(base: &str, layout: GatewayLayout) -> Result<GatewayConfig, Error> {
+        let mut base = Url::parse(base).map_err(Error::InvalidUrl)?;
+        match base.host() {
+            Some(Host::Domain(_)) => {}
+            Some(_) if layout == GatewayLayout::Path => {}
+            _ => return Err(Error::MissingHostname),
+        }
+
+        if !base.path().ends_with('/') {
+            let path = format!("{}/", base.path());
+            base.set_path(&path);
+        }
+        base.set_query(None);
+        base.set_fragment(None);
+
+        Ok(GatewayConfig {
+            base,
+            layout,
+            scheme: DAT_SCHEME,
+        })
+    }
+
+    /// Sets the scheme of the urls mapped back from the gateway, either `dat` or `hyper`, in any case and with an optional trailing `://`.
+    ///
+    /// Fails with [Error::UnsupportedScheme](crate::Error::UnsupportedScheme) for other schemes.
+    pub fn scheme(mut self, scheme: &str) -> Result<GatewayConfig, Error> {
+        let scheme = scheme.trim_end_matches("://");
+        self.scheme = if scheme.eq_ignore_ascii_case("dat") {
+            DAT_SCHEME
+        } else if scheme.eq_ignore_ascii_case("hyper") {
+            HYPER_SCHEME
+        } else {
+            return Err(Error::UnsupportedScheme);
+        };
+        Ok(self)
+    }
+
+    /// Returns the base url of the gateway, with a path ending in `/`.
+    #[inline]
+    pub fn base(&self) -> &Url {
+        &self.base
+    }
+
+    /// Returns the layout of the gateway.
+    #[inline]
+    pub fn layout(&self) -> GatewayLayout {
+        self.layout
+    }
+}
+
+pub(crate) fn to_gateway_url(dat_url: &DatUrl<'_>, config: &GatewayConfig) -> Url {
+    let mut url = config.base.clone();
+    let version = dat_url.version.as_ref().map(ToString::to_string);
+    let path = dat_url.path.as_deref();
+
+    let key = match dat_url.dat_host() {
+        DatHost::Key(key, None) if config.layout == GatewayLayout::Subdomain => Some(key),
+        _ => None,
+    };
+    let archive_path = match key {
+        Some(key) => {
+            let host = format!("{}.{}", key.to_base32(), host_str(&config.base));
+            url.set_host(Some(&host))
+                .expect("a base32 label on a domain is a valid host");
+
+            // The `+` marker is also written without a version when there is no path, or when the path could be read as a version,
+            // since HTTP paths cannot tell `/` apart from no path.
+            match (version, path) {
+                (None, Some(path)) if !path.starts_with("/+") => path.to_owned(),
+                (version, path) => {
+                    format!("/+{}{}", version.unwrap_or_default(), path.unwrap_or(""))
+                }
+            }
+        }
+        None => match version {
+            Some(version) => format!("/{}+{}{}", dat_url.host, version, path.unwrap_or("")),
+            None => format!("/{}{}", dat_url.host, path.unwrap_or("")),
+        },
+    };
+    let gateway_path = format!(
+        "{}{}",
+        &config.base.path()[..config.base.path().len() - 1],
+        archive_path
+    );
+    url.set_path(&gateway_path);
+    url.set_query(dat_url.query.as_deref());
+    url.set_fragment(dat_url.fragment.as_deref());
+    url
+}
+
+pub(crate) fn from_gateway_url(
+    url: &Url,
+    config: &GatewayConfig,
+) -> Result<DatUrl<'static>, Error> {
+    let base = &config.base;
+    if url.scheme() != base.scheme() || url.port_or_known_default() != base.port_or_known_default()
+    {
+        return Err(Error::NotOnGateway);
+    }
+    let rest = url
+        .path()
+        .strip_prefix(base.path())
+        .ok_or(Error::NotOnGateway)?;
+
+    let base_host = host_str(base);
+    let host = url.host_str().unwrap_or("");
+    let dat_url = if host == base_host {
+        if rest.is_empty() {
+            return Err(Error::MissingHostname);
+        }
+        format!("{}{}", config.scheme, rest)
+    } else {
+        let label = host
+            .strip_suffix(base_host)
+            .and_then(|label| label.strip_suffix('.'))
+            .filter(|_| config.layout == GatewayLayout::Subdomain)
+            .ok_or(Error::NotOnGateway)?;
+        let key = DatKey::from_base32(label)?;
+
+        let (version, path) = match rest.strip_prefix('+') {
+            Some(rest) => rest.split_at(rest.find('/').unwrap_or(rest.len())),
+            None => ("", &url.path()[base.path().len() - 1..]),
+        };
+        if version.is_empty() {
+            format!("{}{}{}", config.scheme, key, path)
+        } else {
+            format!("{}{}+{}{}", config.scheme, key, version, path)
+        }
+    };
+
+    let mut dat_url = DatUrl::parse(&dat_url)?.into_owned();
+    dat_url.set_query(url.query());
+    dat_url.set_fragment(url.fragment());
+    Ok(dat_url)
+}
+
+fn host_str(url: &Url) -> &str {
+    url.host_str().unwrap_or("")
+}
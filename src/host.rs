@@ -0,0 +1,80 @@
+use crate::DatKey;
+use core::fmt;
+use core::net::{Ipv4Addr, Ipv6Addr};
+use url::{Host, Url};
+
+/// Classification of the host part of a dat url, alongside the port, if present.
+///
+/// It is derived from the [url::Host] computed while parsing, so routing code can `match` on the kind of host.
+/// Dat urls use non-special schemes, which the `url` crate parses as opaque domains, so keys and IPv4 addresses are detected from the domain text.
+///
+/// # Example
+///
+/// ```rust
+/// use parse_dat_url::{DatHost, DatUrl};
+/// use std::net::Ipv4Addr;
+///
+/// let dat_url = DatUrl::parse("dat://192.0.2.0:3282/file.txt")?;
+/// assert_eq!(DatHost::Ipv4(Ipv4Addr::new(192, 0, 2, 0), Some(3282)), dat_url.dat_host());
+///
+/// let dat_url = DatUrl::parse("example.com+1/file.txt")?;
+/// assert_eq!(DatHost::Domain("example.com", None), dat_url.dat_host());
+/// # Ok::<(), parse_dat_url::Error>(())
+/// ```
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub enum DatHost<'a> {
+    /// The host is a 64 characters hexadecimal archive key.
+    Key(DatKey, Option<u16>),
+    /// The host is a domain name, which must be resolved into a key.
+    Domain(&'a str, Option<u16>),
+    /// The host is an IPv4 address.
+    Ipv4(Ipv4Addr, Option<u16>),
+    /// The host is an IPv6 address.
+    Ipv6(Ipv6Addr, Option<u16>),
+}
+
+impl<'a> DatHost<'a> {
+    pub(crate) fn from_url(url: &'a Url) -> Option<DatHost<'a>> {
+        let port = url.port();
+        let host = match url.host()? {
+            Host::Domain(domain) => {
+                if let Ok(key) = DatKey::from_hex(domain) {
+                    DatHost::Key(key, port)
+                } else if let Ok(address) = domain.parse() {
+                    DatHost::Ipv4(address, port)
+                } else {
+                    DatHost::Domain(domain, port)
+                }
+            }
+            Host::Ipv4(address) => DatHost::Ipv4(address, port),
+            Host::Ipv6(address) => DatHost::Ipv6(address, port),
+        };
+        Some(host)
+    }
+
+    /// Returns the port of the host, if present.
+    #[inline]
+    pub fn port(&self) -> Option<u16> {
+        match self {
+            DatHost::Key(_, port)
+            | DatHost::Domain(_, port)
+            | DatHost::Ipv4(_, port)
+            | DatHost::Ipv6(_, port) => *port,
+        }
+    }
+}
+
+impl<'a> fmt::Display for DatHost<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DatHost::Key(key, _) => write!(f, "{}", key)?,
+            DatHost::Domain(domain, _) => write!(f, "{}", domain)?,
+            DatHost::Ipv4(address, _) => write!(f, "{}", address)?,
+            DatHost::Ipv6(address, _) => write!(f, "[{}]", address)?,
+        };
+        if let Some(port) = self.port() {
+            write!(f, ":{}", port)?;
+        }
+        Ok(())
+    }
+}
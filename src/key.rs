@@ -0,0 +1,332 @@
+use crate::Error;
+use alloc::string::String;
+use blake2b_simd::Params;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::str::FromStr;
+
+const HEX_CHARS: &[u8; 16] = b"0123456789abcdef";
+const BASE32_CHARS: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+const BASE32_LEN: usize = 52;
+
+/// A 32 bytes ed25519 public key, identifying a dat archive.
+///
+/// It is represented on urls as 64 hexadecimal characters, and displayed as lowercase hex.
+/// Comparing two keys for equality runs in constant time.
+///
+/// # Example
+///
+/// ```rust
+/// use parse_dat_url::{DatKey, DatUrl};
+///
+/// let key: DatKey = "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".parse()?;
+/// let dat_url = DatUrl::parse("dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21+1/")?;
+///
+/// assert_eq!(Some(key), dat_url.key());
+/// # Ok::<(), parse_dat_url::Error>(())
+/// ```
+#[derive(Clone, Copy)]
+pub struct DatKey([u8; 32]);
+
+impl DatKey {
+    /// Length of the key, in bytes.
+    pub const LEN: usize = 32;
+
+    /// Builds a key from its raw bytes.
+    #[inline]
+    pub const fn from_bytes(bytes: [u8; 32]) -> DatKey {
+        DatKey(bytes)
+    }
+
+    /// Parses a key from its 64 hexadecimal characters representation, in any case.
+    ///
+    /// Fails with [Error::InvalidKey](crate::Error::InvalidKey) if the value has the wrong length or non hexadecimal characters.
+    pub fn from_hex(hex: &str) -> Result<DatKey, Error> {
+        let hex = hex.as_bytes();
+        if hex.len() != DatKey::LEN * 2 {
+            return Err(Error::InvalidKey);
+        }
+
+        let mut bytes = [0u8; 32];
+        for (byte, pair) in bytes.iter_mut().zip(hex.chunks(2)) {
+            *byte = (hex_value(pair[0])? << 4) | hex_value(pair[1])?;
+        }
+        Ok(DatKey(bytes))
+    }
+
+    /// Returns the lowercase hexadecimal representation of the key.
+    #[inline]
+    pub fn to_hex(&self) -> String {
+        to_hex(&self.0)
+    }
+
+    /// Parses a key from its 52 characters unpadded base32 representation, in any case, using the [RFC 4648](https://tools.ietf.org/html/rfc4648#section-6) alphabet.
+    ///
+    /// Fails with [Error::InvalidKey](crate::Error::InvalidKey) if the value has the wrong length, characters outside the alphabet, or non-zero trailing bits.
+    pub fn from_base32(base32: &str) -> Result<DatKey, Error> {
+        let base32 = base32.as_bytes();
+        if base32.len() != BASE32_LEN {
+            return Err(Error::InvalidKey);
+        }
+
+        let mut bytes = [0u8; 32];
+        let mut buffer = 0u16;
+        let mut bits = 0;
+        let mut index = 0;
+        for &c in base32 {
+            buffer = (buffer << 5) | u16::from(base32_value(c)?);
+            bits += 5;
+            if bits >= 8 {
+                bits -= 8;
+                bytes[index] = (buffer >> bits) as u8;
+                buffer &= (1 << bits) - 1;
+                index += 1;
+            }
+        }
+        if buffer != 0 {
+            return Err(Error::InvalidKey);
+        }
+        Ok(DatKey(bytes))
+    }
+
+    /// Returns the lowercase unpadded base32 representation of the key, 52 characters long, which fits on a DNS label.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use parse_dat_url::DatKey;
+    ///
+    /// let key: DatKey = "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".parse()?;
+    /// let base32 = key.to_base32();
+    ///
+    /// assert_eq!(52, base32.len());
+    /// assert_eq!(key, DatKey::from_base32(&base32)?);
+    /// # Ok::<(), parse_dat_url::Error>(())
+    /// ```
+    pub fn to_base32(&self) -> String {
+        let mut base32 = String::with_capacity(BASE32_LEN);
+        let mut buffer = 0u16;
+        let mut bits = 0;
+        for &byte in self.0.iter() {
+            buffer = (buffer << 8) | u16::from(byte);
+            bits += 8;
+            while bits >= 5 {
+                bits -= 5;
+                base32.push(BASE32_CHARS[usize::from((buffer >> bits) & 0x1f)] as char);
+            }
+            buffer &= (1 << bits) - 1;
+        }
+        if bits > 0 {
+            base32.push(BASE32_CHARS[usize::from((buffer << (5 - bits)) & 0x1f)] as char);
+        }
+        base32
+    }
+
+    /// Returns a reference to the raw bytes of the key.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// Derives the discovery key of the archive, used to find peers on the swarm without revealing the key itself.
+    ///
+    /// It is the BLAKE2b-256 hash of the word `hypercore`, keyed by the public key.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use parse_dat_url::DatKey;
+    ///
+    /// let key: DatKey = "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".parse()?;
+    /// assert_eq!(
+    ///     "7c9bac69b85656ee699f95924f9a6b9101b49ea9d145ea47edb64fb1a8eac874",
+    ///     key.discovery_key().to_string()
+    /// );
+    /// # Ok::<(), parse_dat_url::Error>(())
+    /// ```
+    pub fn discovery_key(&self) -> DiscoveryKey {
+        let hash = Params::new()
+            .hash_length(DiscoveryKey::LEN)
+            .key(&self.0)
+            .hash(b"hypercore");
+
+        let mut bytes = [0u8; 32];
+        bytes.copy_from_slice(hash.as_bytes());
+        DiscoveryKey(bytes)
+    }
+}
+
+fn to_hex(bytes: &[u8; 32]) -> String {
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for byte in bytes.iter() {
+        hex.push(HEX_CHARS[(byte >> 4) as usize] as char);
+        hex.push(HEX_CHARS[(byte & 0xf) as usize] as char);
+    }
+    hex
+}
+
+fn hex_value(c: u8) -> Result<u8, Error> {
+    match c {
+        b'0'..=b'9' => Ok(c - b'0'),
+        b'a'..=b'f' => Ok(c - b'a' + 10),
+        b'A'..=b'F' => Ok(c - b'A' + 10),
+        _ => Err(Error::InvalidKey),
+    }
+}
+
+fn base32_value(c: u8) -> Result<u8, Error> {
+    match c {
+        b'a'..=b'z' => Ok(c - b'a'),
+        b'A'..=b'Z' => Ok(c - b'A'),
+        b'2'..=b'7' => Ok(c - b'2' + 26),
+        _ => Err(Error::InvalidKey),
+    }
+}
+
+impl PartialEq for DatKey {
+    fn eq(&self, other: &DatKey) -> bool {
+        self.0
+            .iter()
+            .zip(other.0.iter())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+    }
+}
+
+impl Eq for DatKey {}
+
+impl Hash for DatKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+impl PartialOrd for DatKey {
+    fn partial_cmp(&self, other: &DatKey) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for DatKey {
+    fn cmp(&self, other: &DatKey) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+impl fmt::Display for DatKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+impl fmt::Debug for DatKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("DatKey").field(&self.to_hex()).finish()
+    }
+}
+
+impl FromStr for DatKey {
+    type Err = Error;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        DatKey::from_hex(s)
+    }
+}
+
+impl core::convert::TryFrom<&str> for DatKey {
+    type Error = Error;
+
+    #[inline]
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        DatKey::from_hex(s)
+    }
+}
+
+impl From<[u8; 32]> for DatKey {
+    #[inline]
+    fn from(bytes: [u8; 32]) -> Self {
+        DatKey(bytes)
+    }
+}
+
+impl From<DatKey> for [u8; 32] {
+    #[inline]
+    fn from(key: DatKey) -> Self {
+        key.0
+    }
+}
+
+impl AsRef<[u8]> for DatKey {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// The 32 bytes discovery key of an archive, derived from its [DatKey](crate::DatKey).
+///
+/// Peers announce and look up archives on the swarm by their discovery key, so the public key is only shared with peers that already know it.
+///
+/// # Example
+///
+/// ```rust
+/// use parse_dat_url::DatUrl;
+///
+/// let dat_url = DatUrl::parse("dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21+1/")?;
+/// let discovery_key = dat_url.discovery_key().expect("url host is a key");
+/// println!("{}", discovery_key);
+/// # Ok::<(), parse_dat_url::Error>(())
+/// ```
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct DiscoveryKey([u8; 32]);
+
+impl DiscoveryKey {
+    /// Length of the discovery key, in bytes.
+    pub const LEN: usize = 32;
+
+    /// Builds a discovery key from its raw bytes.
+    #[inline]
+    pub const fn from_bytes(bytes: [u8; 32]) -> DiscoveryKey {
+        DiscoveryKey(bytes)
+    }
+
+    /// Returns the lowercase hexadecimal representation of the discovery key.
+    #[inline]
+    pub fn to_hex(&self) -> String {
+        to_hex(&self.0)
+    }
+
+    /// Returns a reference to the raw bytes of the discovery key.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl fmt::Display for DiscoveryKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+impl fmt::Debug for DiscoveryKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("DiscoveryKey").field(&self.to_hex()).finish()
+    }
+}
+
+impl From<DiscoveryKey> for [u8; 32] {
+    #[inline]
+    fn from(key: DiscoveryKey) -> Self {
+        key.0
+    }
+}
+
+impl AsRef<[u8]> for DiscoveryKey {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
@@ -1,5 +1,6 @@
 #![doc(html_root_url = "https://docs.rs/parse-dat-url/0.1.0/")]
 #![deny(missing_docs)]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
 //! # parse-dat-url
 //! url parser to support versioned [dat](https://dat.foundation) URLs
@@ -27,47 +28,80 @@
 //! }
 //! ```
 
+extern crate alloc;
+
+use alloc::borrow::{Cow, ToOwned};
+use alloc::format;
+use alloc::string::{String, ToString};
 use core::fmt;
 use core::str::FromStr;
-use lazy_static::lazy_static;
-use regex::Regex;
-use std::borrow::Cow;
 use url::Url;
 
+mod builder;
+mod find;
+mod gateway;
+mod host;
+mod key;
+mod options;
+mod parser;
+mod pin;
+#[cfg(feature = "std")]
+pub mod resolve;
 #[cfg(feature = "serde")]
-mod serde;
+pub mod serde;
+mod version;
 
-lazy_static! {
-    static ref VERSION_REGEX: Regex = Regex::new(
-        r#"(?i)^(?P<scheme>\w+://)?(?P<hostname>[^/+]+)(\+(?P<version>[^/]+))?(?P<path>.*)$"#
-    )
-    .expect("Version regex not valid");
-}
+pub use builder::DatUrlBuilder;
+pub use find::{find_iter, FindIter};
+pub use gateway::{GatewayConfig, GatewayLayout};
+pub use host::DatHost;
+pub use key::{DatKey, DiscoveryKey};
+pub use options::ParseOptions;
+pub use pin::{apply_pins, pin_versions, Pin};
+pub use version::Version;
+
+const DAT_SCHEME: &str = "dat://";
+const HYPER_SCHEME: &str = "hyper://";
 
 /// Possible errors returned by the parsing operation
 #[derive(Debug, Eq, PartialEq)]
 pub enum Error {
-    /// Correspond to invalid regex matching.
+    /// Correspond to a value not matching the dat url grammar, such as an empty string or a missing host.
     InvalidRegex,
     /// Correspond to invalid domain or url, such as bad IPv6 address, or bad encoding on domain names.
     /// Contains a reference to the original `url` parssing error inside.
     InvalidUrl(url::ParseError),
     /// Correspond to missing domain on data.
     MissingHostname,
+    /// Correspond to a malformed version, such as `+01x` or `+c1x`.
+    InvalidVersion,
+    /// Correspond to a value which is not a 64 characters hexadecimal key.
+    InvalidKey,
+    /// Correspond to a scheme not allowed by the [ParseOptions](crate::ParseOptions) used.
+    UnsupportedScheme,
+    /// Correspond to a versioned url when the [ParseOptions](crate::ParseOptions) used forbid versions.
+    UnexpectedVersion,
+    /// Correspond to a url which does not belong to the [GatewayConfig](crate::GatewayConfig) used, such as another host or path prefix.
+    NotOnGateway,
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Error::InvalidRegex => write!(f, "regex defined on library can't match the value")?,
+            Error::InvalidRegex => write!(f, "value does not match the dat url grammar")?,
             Error::InvalidUrl(_) => write!(f, "malformed url not conforming to URL Spec")?,
             Error::MissingHostname => write!(f, "missing hostname on url")?,
+            Error::InvalidVersion => write!(f, "malformed version on url")?,
+            Error::InvalidKey => write!(f, "value is not a 64 characters hexadecimal key")?,
+            Error::UnsupportedScheme => write!(f, "scheme not allowed on url")?,
+            Error::UnexpectedVersion => write!(f, "version not allowed on url")?,
+            Error::NotOnGateway => write!(f, "url not served by the gateway")?,
         };
         Ok(())
     }
 }
 
-impl std::error::Error for Error {}
+impl core::error::Error for Error {}
 
 /// Main structure exported. It holds a reference to the string itself, but it is capable of becoming owned, in order to send it across threads.
 ///
@@ -88,8 +122,10 @@ impl std::error::Error for Error {}
 pub struct DatUrl<'a> {
     scheme: Cow<'a, str>,
     host: Cow<'a, str>,
-    version: Option<Cow<'a, str>>,
+    version: Option<Version<'a>>,
     path: Option<Cow<'a, str>>,
+    query: Option<Cow<'a, str>>,
+    fragment: Option<Cow<'a, str>>,
     url: Url,
 }
 
@@ -102,50 +138,246 @@ impl<'a> fmt::Display for DatUrl<'a> {
         if let Some(path) = &self.path {
             write!(f, "{}", path)?;
         }
+        if let Some(query) = &self.query {
+            write!(f, "?{}", query)?;
+        }
+        if let Some(fragment) = &self.fragment {
+            write!(f, "#{}", fragment)?;
+        }
         Ok(())
     }
 }
 
 impl<'a> DatUrl<'a> {
-    fn url_str(scheme: &str, host: &str, path: &Option<&str>) -> String {
-        format!("{}{}{}", scheme, host, path.map_or("", |path| &path))
+    fn url_str(
+        scheme: &str,
+        host: &str,
+        path: Option<&str>,
+        query: Option<&str>,
+        fragment: Option<&str>,
+    ) -> String {
+        let mut url = format!("{}{}{}", scheme, host, path.unwrap_or(""));
+        if let Some(query) = query {
+            url.push('?');
+            url.push_str(query);
+        }
+        if let Some(fragment) = fragment {
+            url.push('#');
+            url.push_str(fragment);
+        }
+        url
     }
 
     /// Main parsing operation. Returns a struct which makes reference to the `&str` passed, with the same lifetime.
     ///
     /// It is capable to clone the structure into a onwed reference, as it uses [Cow](std::borrow::Cow) internally.
-    pub fn parse(url: &str) -> Result<DatUrl, Error> {
-        let capture = VERSION_REGEX.captures(url).ok_or(Error::InvalidRegex)?;
+    ///
+    /// The version part, if present, is parsed into a [Version](crate::Version), failing with [Error::InvalidVersion](crate::Error::InvalidVersion) when malformed.
+    ///
+    /// Any scheme is accepted. Use [DatUrl::options](#method.options) to restrict what is accepted.
+    #[inline]
+    pub fn parse(url: &str) -> Result<DatUrl<'_>, Error> {
+        DatUrl::options().parse(url)
+    }
+
+    /// Creates a [ParseOptions](crate::ParseOptions) builder, to configure how urls are parsed.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use parse_dat_url::{DatUrl, Error};
+    ///
+    /// let options = DatUrl::options().allowed_schemes(&["dat"]);
+    /// assert!(options.parse("dat://example.com/").is_ok());
+    /// assert_eq!(Err(Error::UnsupportedScheme), options.parse("ftp://example.com/"));
+    /// ```
+    #[inline]
+    pub fn options<'o>() -> ParseOptions<'o> {
+        ParseOptions::default()
+    }
 
-        let version = capture.name("version").map(|c| c.as_str());
+    /// Creates a [DatUrlBuilder](crate::DatUrlBuilder), to construct urls programmatically.
+    #[inline]
+    pub fn builder() -> DatUrlBuilder {
+        DatUrlBuilder::new()
+    }
 
-        let host = capture
-            .name("hostname")
-            .ok_or(Error::MissingHostname)?
-            .as_str();
+    fn parse_with(url: &'a str, options: &ParseOptions<'_>) -> Result<DatUrl<'a>, Error> {
+        let parts = parser::split(url).ok_or(Error::InvalidRegex)?;
 
-        let path = capture.name("path").and_then(|c| match c.as_str() {
-            "" => None,
-            s => Some(s),
-        });
+        let version = parts.version.map(Version::parse).transpose()?;
+        let host = parts.host;
+        let path = Some(parts.path).filter(|path| !path.is_empty());
 
-        let scheme = capture
-            .name("scheme")
-            .map(|c| c.as_str())
-            .unwrap_or("dat://");
+        let scheme: Cow<'a, str> = match parts.scheme {
+            Some(scheme) => scheme.into(),
+            None if options.default_scheme == "dat" => DAT_SCHEME.into(),
+            None => format!("{}://", options.default_scheme).into(),
+        };
 
-        let valid_url = Url::parse(&DatUrl::url_str(&scheme, &host, &path))
-            .map_err(Error::InvalidUrl)?;
+        if !options.is_allowed(&scheme) {
+            return Err(Error::UnsupportedScheme);
+        }
+        if options.forbid_version && version.is_some() {
+            return Err(Error::UnexpectedVersion);
+        }
+        // Matches the key of [dat_host](#method.dat_host), which leaves the port out.
+        let host_without_port = host.rsplit_once(':').map_or(host, |(host, _)| host);
+        if options.require_key && DatKey::from_hex(host_without_port).is_err() {
+            return Err(Error::InvalidKey);
+        }
+
+        let query = parts.query;
+        let fragment = parts.fragment;
+
+        if DatUrl::scheme_is(&scheme, HYPER_SCHEME)
+            && !version.as_ref().is_none_or(Version::is_hyper_compatible)
+        {
+            return Err(Error::InvalidVersion);
+        }
+
+        // Without a version to strip nor a scheme to add, the input is already the url to validate.
+        let valid_url = if parts.scheme.is_some() && parts.version.is_none() {
+            Url::parse(url)
+        } else {
+            Url::parse(&DatUrl::url_str(&scheme, host, path, query, fragment))
+        }
+        .map_err(Error::InvalidUrl)?;
+        if valid_url.host().is_none() {
+            return Err(Error::MissingHostname);
+        }
 
         Ok(DatUrl {
-            version: version.map(Cow::from),
+            version,
             host: host.into(),
             path: path.map(Cow::from),
-            scheme: scheme.into(),
+            query: query.map(Cow::from),
+            fragment: fragment.map(Cow::from),
+            scheme,
             url: valid_url,
         })
     }
 
+    /// Checks the host, with an optional port, as an opaque host kept as written, the way [parse](#method.parse) reads it.
+    pub(crate) fn check_host(host: &str) -> Result<(), Error> {
+        if host.is_empty() {
+            return Err(Error::MissingHostname);
+        }
+        if host.contains(&['/', '+', '?', '#'][..]) {
+            return Err(Error::InvalidUrl(url::ParseError::InvalidDomainCharacter));
+        }
+        let url = Url::parse(&format!("{}{}", DAT_SCHEME, host)).map_err(Error::InvalidUrl)?;
+        if url.host().is_none() {
+            return Err(Error::MissingHostname);
+        }
+        Ok(())
+    }
+
+    fn scheme_is(scheme: &str, expected: &str) -> bool {
+        scheme.eq_ignore_ascii_case(expected)
+    }
+
+    fn with_scheme(&self, scheme: &'static str) -> Result<DatUrl<'a>, Error> {
+        let url = Url::parse(&DatUrl::url_str(
+            scheme,
+            &self.host,
+            self.path.as_deref(),
+            self.query.as_deref(),
+            self.fragment.as_deref(),
+        ))
+        .map_err(Error::InvalidUrl)?;
+
+        Ok(DatUrl {
+            scheme: scheme.into(),
+            url,
+            ..self.clone()
+        })
+    }
+
+    /// Returns true if the url uses the `dat://` scheme, in any case.
+    #[inline]
+    pub fn is_dat(&self) -> bool {
+        DatUrl::scheme_is(&self.scheme, DAT_SCHEME)
+    }
+
+    /// Returns true if the url uses the `hyper://` scheme of the [Hypercore Protocol](https://hypercore-protocol.org), in any case.
+    #[inline]
+    pub fn is_hyper(&self) -> bool {
+        DatUrl::scheme_is(&self.scheme, HYPER_SCHEME)
+    }
+
+    /// Converts the url into the `hyper://` form, keeping the same host, version, path, query and fragment.
+    ///
+    /// `hyper://` urls only support version numbers and `+latest`, so other versions fail with [Error::InvalidVersion](crate::Error::InvalidVersion).
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use parse_dat_url::DatUrl;
+    ///
+    /// let dat_url = DatUrl::parse("dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21+5/file.txt")?;
+    /// let hyper_url = dat_url.to_hyper()?;
+    /// assert_eq!(
+    ///     "hyper://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21+5/file.txt",
+    ///     hyper_url.to_string()
+    /// );
+    /// assert_eq!(dat_url, hyper_url.to_dat()?);
+    /// # Ok::<(), parse_dat_url::Error>(())
+    /// ```
+    pub fn to_hyper(&self) -> Result<DatUrl<'a>, Error> {
+        if !self
+            .version
+            .as_ref()
+            .is_none_or(Version::is_hyper_compatible)
+        {
+            return Err(Error::InvalidVersion);
+        }
+        self.with_scheme(HYPER_SCHEME)
+    }
+
+    /// Converts the url into the `dat://` form, keeping the same host, version, path, query and fragment.
+    ///
+    /// Fails with [Error::InvalidUrl](crate::Error::InvalidUrl) if the parts are not valid under the `dat://` scheme,
+    /// which may happen when converting from special schemes such as `https://`.
+    pub fn to_dat(&self) -> Result<DatUrl<'a>, Error> {
+        self.with_scheme(DAT_SCHEME)
+    }
+
+    /// Maps the url to where a dat-to-HTTP gateway serves it, following the [GatewayLayout](crate::GatewayLayout) of the configuration.
+    ///
+    /// The scheme is not part of gateway urls, while the host, version, path, query and fragment are kept as written,
+    /// so [from_gateway_url](#method.from_gateway_url) gives them back unchanged, as long as the url is in its serialized form,
+    /// such as the one returned by [normalize](#method.normalize).
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use parse_dat_url::{DatUrl, GatewayConfig};
+    ///
+    /// let config = GatewayConfig::path("http://gateway/")?;
+    /// let dat_url = DatUrl::parse("dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21+5/index.html")?;
+    ///
+    /// let url = dat_url.to_gateway_url(&config);
+    /// assert_eq!(
+    ///     "http://gateway/584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21+5/index.html",
+    ///     url.as_str()
+    /// );
+    /// assert_eq!(dat_url, DatUrl::from_gateway_url(&url, &config)?);
+    /// # Ok::<(), parse_dat_url::Error>(())
+    /// ```
+    pub fn to_gateway_url(&self, config: &GatewayConfig) -> Url {
+        gateway::to_gateway_url(self, config)
+    }
+
+    /// Maps a url served by a dat-to-HTTP gateway back to the dat url, using the scheme of the configuration.
+    ///
+    /// Fails with [Error::NotOnGateway](crate::Error::NotOnGateway) if the url is not under the gateway base url,
+    /// with [Error::InvalidKey](crate::Error::InvalidKey) if a subdomain is not a base32 key,
+    /// and like [parse](#method.parse) if the rest is not a valid dat url.
+    pub fn from_gateway_url(url: &Url, config: &GatewayConfig) -> Result<DatUrl<'static>, Error> {
+        gateway::from_gateway_url(url, config)
+    }
+
     /// Converts a [DatUrl](parse_dat_url::DatUrl) with a `'a` lifetime into a owned struct, with the `'static` lifetime.
     ///
     /// ## Examples
@@ -166,37 +398,369 @@ impl<'a> DatUrl<'a> {
     /// ```
     pub fn into_owned(self) -> DatUrl<'static> {
         DatUrl {
-            host: self.host.to_owned().into_owned().into(),
-            scheme: self.scheme.to_owned().into_owned().into(),
-            version: self.version.to_owned().map(|v| v.into_owned().into()),
-            path: self.path.to_owned().map(|p| p.into_owned().into()),
+            host: self.host.into_owned().into(),
+            scheme: self.scheme.into_owned().into(),
+            version: self.version.map(Version::into_owned),
+            path: self.path.map(|p| p.into_owned().into()),
+            query: self.query.map(|q| q.into_owned().into()),
+            fragment: self.fragment.map(|f| f.into_owned().into()),
             url: self.url,
         }
     }
 
     /// Returns a reference to the scheme used on the url. If no scheme is provided on the string, it fallsback to `dat://`
+    ///
+    /// Both `dat://` and `hyper://` are recognised, see [is_dat](#method.is_dat) and [is_hyper](#method.is_hyper).
     #[inline]
-    pub fn scheme(&self) -> &Cow<str> {
+    pub fn scheme(&self) -> &Cow<'_, str> {
         &self.scheme
     }
 
     /// Returns the host part of the url.
     #[inline]
-    pub fn host(&self) -> &Cow<str> {
+    pub fn host(&self) -> &Cow<'_, str> {
         &self.host
     }
 
-    /// Returns a reference to the version on the dat url, if present.
+    /// Returns the classification of the host part of the url, alongside its port.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use parse_dat_url::{DatHost, DatUrl};
+    ///
+    /// let dat_url = DatUrl::parse("dat://[2001:DB8::0]:3282/")?;
+    /// match dat_url.dat_host() {
+    ///     DatHost::Ipv6(address, port) => assert_eq!((address.segments()[0], port), (0x2001, Some(3282))),
+    ///     other => panic!("unexpected host {}", other),
+    /// }
+    /// # Ok::<(), parse_dat_url::Error>(())
+    /// ```
+    pub fn dat_host(&self) -> DatHost<'_> {
+        DatHost::from_url(&self.url).expect("dat urls are always parsed with a host")
+    }
+
+    /// Returns the archive key, if the host of the url is a 64 characters hexadecimal key instead of a domain name, with or without a port.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use parse_dat_url::DatUrl;
+    ///
+    /// let dat_url = DatUrl::parse("dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21/")?;
+    /// assert!(dat_url.key().is_some());
+    ///
+    /// let dat_url = DatUrl::parse("dat://example.com/")?;
+    /// assert!(dat_url.key().is_none());
+    /// # Ok::<(), parse_dat_url::Error>(())
+    /// ```
+    pub fn key(&self) -> Option<DatKey> {
+        match self.dat_host() {
+            DatHost::Key(key, _) => Some(key),
+            _ => None,
+        }
+    }
+
+    /// Returns the discovery key of the archive, if the host of the url is a key.
+    ///
+    /// See [DatKey::discovery_key](crate::DatKey::discovery_key) for the derivation.
     #[inline]
-    pub fn version(&self) -> &Option<Cow<str>> {
+    pub fn discovery_key(&self) -> Option<DiscoveryKey> {
+        self.key().map(|key| key.discovery_key())
+    }
+
+    /// Returns a reference to the typed version on the dat url, if present.
+    #[inline]
+    pub fn version(&self) -> &Option<Version<'a>> {
         &self.version
     }
 
-    /// Returns a reference to the path on the dat url, if present.
+    /// Returns a reference to the path on the dat url, if present. It does not include the query string nor the fragment.
     #[inline]
-    pub fn path(&self) -> &Option<Cow<str>> {
+    pub fn path(&self) -> &Option<Cow<'_, str>> {
         &self.path
     }
+
+    /// Returns a reference to the query string on the dat url, without the leading `?`, if present.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use parse_dat_url::DatUrl;
+    ///
+    /// let dat_url = DatUrl::parse("dat://example.com/file.txt?x=1#top")?;
+    /// assert_eq!(&Some("/file.txt".into()), dat_url.path());
+    /// assert_eq!(&Some("x=1".into()), dat_url.query());
+    /// assert_eq!(&Some("top".into()), dat_url.fragment());
+    /// # Ok::<(), parse_dat_url::Error>(())
+    /// ```
+    #[inline]
+    pub fn query(&self) -> &Option<Cow<'_, str>> {
+        &self.query
+    }
+
+    /// Parses the query string as `application/x-www-form-urlencoded` and returns an iterator of decoded `(key, value)` pairs.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use parse_dat_url::DatUrl;
+    ///
+    /// let dat_url = DatUrl::parse("dat://example.com/search?q=dat+url&page=2")?;
+    /// let pairs: Vec<(String, String)> = dat_url.query_pairs().into_owned().collect();
+    /// assert_eq!(pairs, vec![("q".into(), "dat url".into()), ("page".into(), "2".into())]);
+    /// # Ok::<(), parse_dat_url::Error>(())
+    /// ```
+    #[inline]
+    pub fn query_pairs(&self) -> url::form_urlencoded::Parse<'_> {
+        self.url.query_pairs()
+    }
+
+    /// Returns a reference to the fragment on the dat url, without the leading `#`, if present.
+    #[inline]
+    pub fn fragment(&self) -> &Option<Cow<'_, str>> {
+        &self.fragment
+    }
+
+    /// Resolves a relative reference against this url, following [RFC 3986](https://tools.ietf.org/html/rfc3986#section-5).
+    ///
+    /// The result keeps the scheme, host and version of this url, unless the reference is absolute,
+    /// such as `dat://other-key/` or `//other-key/`, in which case the reference is parsed on its own.
+    ///
+    /// References with a scheme other than `dat` or `hyper`, such as `https://example.com/` or `mailto:a@b`,
+    /// fail with [Error::UnsupportedScheme](crate::Error::UnsupportedScheme).
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use parse_dat_url::DatUrl;
+    ///
+    /// let base = DatUrl::parse("dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21+5/blog/post.html")?;
+    ///
+    /// assert_eq!(
+    ///     "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21+5/img/a.png",
+    ///     base.join("../img/a.png")?.to_string()
+    /// );
+    /// assert_eq!(
+    ///     "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21+5/blog/post.html?page=2",
+    ///     base.join("?page=2")?.to_string()
+    /// );
+    /// assert_eq!("dat://example.com/", base.join("dat://example.com/")?.to_string());
+    /// # Ok::<(), parse_dat_url::Error>(())
+    /// ```
+    pub fn join(&self, reference: &str) -> Result<DatUrl<'static>, Error> {
+        if let Some(scheme) = DatUrl::scheme_of(reference) {
+            if !scheme.eq_ignore_ascii_case("dat") && !scheme.eq_ignore_ascii_case("hyper") {
+                return Err(Error::UnsupportedScheme);
+            }
+            return DatUrl::parse(reference).map(DatUrl::into_owned);
+        }
+        if let Some(network_path) = reference.strip_prefix("//") {
+            let url = format!("{}{}", self.scheme, network_path);
+            return DatUrl::parse(&url).map(DatUrl::into_owned);
+        }
+
+        let url = self.url.join(reference).map_err(Error::InvalidUrl)?;
+        Ok(DatUrl {
+            scheme: self.scheme.clone().into_owned().into(),
+            host: self.host.clone().into_owned().into(),
+            version: self.version.clone().map(Version::into_owned),
+            path: Some(url.path())
+                .filter(|path| !path.is_empty())
+                .map(|path| path.to_owned().into()),
+            query: url.query().map(|query| query.to_owned().into()),
+            fragment: url.fragment().map(|fragment| fragment.to_owned().into()),
+            url,
+        })
+    }
+
+    /// Returns the scheme of the reference, without the `:`, if it has one according to RFC 3986.
+    fn scheme_of(reference: &str) -> Option<&str> {
+        let (scheme, _) = reference.split_once(':')?;
+        let mut chars = scheme.chars();
+        let valid = chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.');
+        if valid {
+            Some(scheme)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the canonical form of the url, so urls pointing to the same archive, version and file compare as equal.
+    ///
+    /// The normalization applies, in order:
+    ///
+    /// 1. The scheme is lowercased. Urls without a scheme already use `dat://`.
+    /// 2. Keys and domain names are lowercased, and IP addresses are serialized in their shortest form, such as `[2001:db8::]`. Ports are kept.
+    /// 3. `+latest` is dropped, as it is the same as having no version. Other versions are kept as written.
+    /// 4. Dot segments, such as `/a/./b/../c`, are collapsed, and the path is percent-encoded as the [URL Standard](https://url.spec.whatwg.org/) does.
+    /// 5. An empty path becomes `/`, as both point to the root of the archive. Other trailing slashes are kept.
+    /// 6. Query string and fragment are kept, percent-encoded as the URL Standard does.
+    ///
+    /// Normalizing a normalized url returns the same url.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use parse_dat_url::DatUrl;
+    ///
+    /// let a = DatUrl::parse("DAT://584FAA05D394190AB1A3F0240607F9BF2B7E2BD9968830A11CF77DB0CEA36A21+latest")?;
+    /// let b = DatUrl::parse("584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21/")?;
+    ///
+    /// assert_ne!(a, b);
+    /// assert_eq!(a.normalize(), b.normalize());
+    /// assert_eq!(
+    ///     "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21/",
+    ///     a.normalize().to_string()
+    /// );
+    /// # Ok::<(), parse_dat_url::Error>(())
+    /// ```
+    pub fn normalize(&self) -> DatUrl<'static> {
+        let scheme = self.scheme.to_ascii_lowercase();
+        let host = match self.dat_host() {
+            DatHost::Domain(domain, port) => {
+                DatHost::Domain(&domain.to_ascii_lowercase(), port).to_string()
+            }
+            host => host.to_string(),
+        };
+        let version = match &self.version {
+            Some(Version::Latest) | None => None,
+            Some(version) => Some(version.clone().into_owned()),
+        };
+        let path = match self.url.path() {
+            "" => "/",
+            path => path,
+        };
+        let query = self.url.query();
+        let fragment = self.url.fragment();
+
+        let url = Url::parse(&DatUrl::url_str(
+            &scheme,
+            &host,
+            Some(path),
+            query,
+            fragment,
+        ))
+        .expect("normalized parts come from a valid url");
+
+        DatUrl {
+            path: Some(path.to_owned().into()),
+            query: query.map(|query| query.to_owned().into()),
+            fragment: fragment.map(|fragment| fragment.to_owned().into()),
+            scheme: scheme.into(),
+            host: host.into(),
+            version,
+            url,
+        }
+    }
+
+    /// Changes the host of the url, which may be a key, a domain name or an IP address, with an optional port.
+    ///
+    /// Fails with [Error::MissingHostname](crate::Error::MissingHostname) for an empty host,
+    /// and with [Error::InvalidUrl](crate::Error::InvalidUrl) for malformed hosts, leaving the url untouched.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use parse_dat_url::DatUrl;
+    ///
+    /// let mut dat_url = DatUrl::parse("dat://example.com+5/file.txt")?;
+    /// dat_url.set_host("584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21")?;
+    /// assert_eq!(
+    ///     "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21+5/file.txt",
+    ///     dat_url.to_string()
+    /// );
+    /// # Ok::<(), parse_dat_url::Error>(())
+    /// ```
+    pub fn set_host(&mut self, host: &str) -> Result<(), Error> {
+        DatUrl::check_host(host)?;
+
+        let url = Url::parse(&DatUrl::url_str(
+            &self.scheme,
+            host,
+            self.path.as_deref(),
+            self.query.as_deref(),
+            self.fragment.as_deref(),
+        ))
+        .map_err(Error::InvalidUrl)?;
+        if url.host().is_none() {
+            return Err(Error::MissingHostname);
+        }
+
+        self.host = host.to_owned().into();
+        self.url = url;
+        Ok(())
+    }
+
+    /// Pins the url to the given version.
+    ///
+    /// Fails with [Error::InvalidVersion](crate::Error::InvalidVersion) if the version is not supported by `hyper://` urls,
+    /// or if it would be read back as another version, such as `Version::Tag("1x")` or a tag containing a `/`, leaving the url untouched.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use parse_dat_url::{DatUrl, Version};
+    ///
+    /// let mut dat_url = DatUrl::parse("dat://example.com/file.txt")?;
+    /// dat_url.set_version(Version::Number(5))?;
+    /// assert_eq!("dat://example.com+5/file.txt", dat_url.to_string());
+    ///
+    /// dat_url.clear_version();
+    /// assert_eq!("dat://example.com/file.txt", dat_url.to_string());
+    /// # Ok::<(), parse_dat_url::Error>(())
+    /// ```
+    pub fn set_version(&mut self, version: Version<'a>) -> Result<(), Error> {
+        if !version.round_trips() || (self.is_hyper() && !version.is_hyper_compatible()) {
+            return Err(Error::InvalidVersion);
+        }
+        self.version = Some(version);
+        Ok(())
+    }
+
+    /// Removes the version of the url, pointing it to the latest version of the archive.
+    #[inline]
+    pub fn clear_version(&mut self) {
+        self.version = None;
+    }
+
+    /// Changes the path of the url. An empty path removes it.
+    ///
+    /// A leading `/` is added when missing, and `?` and `#` are percent-encoded, so they are not mistaken for the query string or fragment.
+    pub fn set_path(&mut self, path: &str) {
+        if path.is_empty() {
+            self.path = None;
+        } else {
+            let mut encoded = String::with_capacity(path.len() + 1);
+            if !path.starts_with('/') {
+                encoded.push('/');
+            }
+            for c in path.chars() {
+                match c {
+                    '?' => encoded.push_str("%3F"),
+                    '#' => encoded.push_str("%23"),
+                    c => encoded.push(c),
+                }
+            }
+            self.path = Some(encoded.into());
+        }
+        self.url.set_path(self.path.as_deref().unwrap_or(""));
+    }
+
+    /// Changes the query string of the url, without the leading `?`. `None` removes it.
+    ///
+    /// A `#` is percent-encoded, so it is not mistaken for the fragment.
+    pub fn set_query(&mut self, query: Option<&str>) {
+        self.query = query.map(|query| query.replace('#', "%23").into());
+        self.url.set_query(self.query.as_deref());
+    }
+
+    /// Changes the fragment of the url, without the leading `#`. `None` removes it.
+    pub fn set_fragment(&mut self, fragment: Option<&str>) {
+        self.fragment = fragment.map(|fragment| fragment.to_owned().into());
+        self.url.set_fragment(self.fragment.as_deref());
+    }
 }
 
 impl<'a> FromStr for DatUrl<'a> {
@@ -207,7 +771,7 @@ impl<'a> FromStr for DatUrl<'a> {
     }
 }
 
-impl<'a> std::convert::TryFrom<&'a str> for DatUrl<'a> {
+impl<'a> core::convert::TryFrom<&'a str> for DatUrl<'a> {
     type Error = Error;
 
     #[inline]
@@ -234,7 +798,7 @@ impl<'a> From<DatUrl<'a>> for Url {
 mod tests {
     use pretty_assertions::assert_eq;
 
-    use super::DatUrl;
+    use super::{DatUrl, Version};
     use url::Url;
 
     // TODO:
@@ -243,10 +807,8 @@ mod tests {
     #[cfg(doctest)]
     doc_comment::doctest!("../README.md");
 
-    #[test]
-    fn it_parses_the_urls() -> Result<(), super::Error> {
-        let inputs: &str =
-            "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21+0.0.0.1/
+    const INPUTS: &str =
+        "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21+0.0.0.1/
 dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21+1/
 dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21+c1/
 dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21+v1/
@@ -335,73 +897,217 @@ example.com/path/to+file.txt
 192.0.2.0+0.0.0.1/path/to+file.txt
 192.0.2.0/path/to+file.txt
 [2001:DB8::0]
-[2001:DB8::0]+0.0.0.1/path/to+file.txt";
+[2001:DB8::0]+0.0.0.1/path/to+file.txt
+dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21+1/path/to+file.txt?x=1#top
+example.com+v1.0.0?x=1
+example.com#top
+example.com/?#";
 
+    #[test]
+    fn it_splits_like_the_previous_regex() {
+        let regex = regex::Regex::new(
+            r#"(?i)^(?P<scheme>\w+://)?(?P<hostname>[^/+?#]+)(\+(?P<version>[^/?#]+))?(?P<path>[^?#]*)(\?(?P<query>[^#]*))?(#(?P<fragment>.*))?$"#,
+        )
+        .expect("Version regex not valid");
+
+        let edge_cases = &[
+            "",
+            "/",
+            "+1",
+            "?x",
+            "#x",
+            "dat://",
+            "dat:///path",
+            "dat://+1/path",
+            "dat://example.com+/path",
+            "dat://example.com+",
+            "dat://example.com+1+2/path",
+            "dat://example.com/a+b/c?d+e#f+g",
+            "dat://example.com?x=1?y=2#a#b",
+            "dat://example.com#line\nbreak",
+            "dat://exa\nmple.com/pa\nth?que\nry",
+            "ab://cd://ef",
+            "a+b://example.com",
+            "h_y-per://example.com",
+            "ünï://example.com/ü",
+            "example.com:3282+1/file.txt",
+            "user:pass@example.com/",
+        ];
+
+        for url in INPUTS.lines().chain(edge_cases.iter().copied()) {
+            let expected = regex.captures(url).map(|capture| super::parser::Parts {
+                scheme: capture.name("scheme").map(|c| c.as_str()),
+                host: capture.name("hostname").map_or("", |c| c.as_str()),
+                version: capture.name("version").map(|c| c.as_str()),
+                path: capture.name("path").map_or("", |c| c.as_str()),
+                query: capture.name("query").map(|c| c.as_str()),
+                fragment: capture.name("fragment").map(|c| c.as_str()),
+            });
+            assert_eq!(expected, super::parser::split(url), "{:?}", url);
+        }
+    }
+
+    #[test]
+    fn it_normalizes_the_urls() -> Result<(), super::Error> {
+        for url in INPUTS.lines() {
+            let normalized = DatUrl::parse(url)?.normalize();
+            let canonical = normalized.to_string();
+
+            assert_eq!(normalized, normalized.normalize());
+            assert_eq!(normalized, DatUrl::parse(&canonical)?);
+            assert!(canonical.starts_with("dat://"));
+            assert!(!canonical.contains("+latest"));
+            assert!(normalized.path().is_some());
+
+            let key = "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21";
+            let variants = vec![
+                url.replace(key, &key.to_uppercase()),
+                format!("dat://{}", url.trim_start_matches("dat://")),
+                format!("DAT://{}", url.trim_start_matches("dat://")),
+            ];
+            for variant in variants {
+                assert_eq!(
+                    normalized,
+                    DatUrl::parse(&variant)?.normalize(),
+                    "{}",
+                    variant
+                );
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn it_normalizes_equivalent_urls_to_the_same_value() -> Result<(), super::Error> {
+        let key = "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21";
+        let equivalents = &[
+            format!("dat://{}+latest/", key),
+            format!("dat://{}+latest", key),
+            format!("dat://{}/", key),
+            key.to_string(),
+            format!("DAT://{}/", key.to_uppercase()),
+            format!("{}+latest/a/../", key),
+            format!("{}/./", key),
+        ];
+
+        let canonical = format!("dat://{}/", key);
+        for url in equivalents {
+            assert_eq!(
+                canonical,
+                DatUrl::parse(url)?.normalize().to_string(),
+                "{}",
+                url
+            );
+        }
+
+        let canonical = "dat://example.com+v1/path/to+file.txt";
+        for url in &[
+            "EXAMPLE.com+v1/path/to+file.txt",
+            "dat://example.COM+v1/path/./to+file.txt",
+            "dat://example.com+v1/path/x/../to+file.txt",
+        ] {
+            assert_eq!(
+                canonical,
+                DatUrl::parse(url)?.normalize().to_string(),
+                "{}",
+                url
+            );
+        }
+
+        assert_eq!(
+            "dat://[2001:db8::]:3282+0.0.0.1/path/to+file.txt",
+            DatUrl::parse("[2001:DB8::0]:3282+0.0.0.1/path/to+file.txt")?
+                .normalize()
+                .to_string()
+        );
+        assert_ne!(
+            DatUrl::parse("dat://example.com/dir")?.normalize(),
+            DatUrl::parse("dat://example.com/dir/")?.normalize()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn it_parses_the_urls() -> Result<(), super::Error> {
         let outputs: &[DatUrl] = &[
             DatUrl {
-                version: Some("0.0.0.1".into()),
+                version: Some(Version::Tag("0.0.0.1".into())),
                 host: "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".into(),
                 path: Some("/".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
-                    "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21",
+                    "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21/",
                 )
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("1".into()),
+                version: Some(Version::Number(1)),
                 host: "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".into(),
                 path: Some("/".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
-                    "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21",
+                    "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21/",
                 )
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("c1".into()),
+                version: Some(Version::Content(1)),
                 host: "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".into(),
                 path: Some("/".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
-                    "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21",
+                    "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21/",
                 )
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("v1".into()),
+                version: Some(Version::Tag("v1".into())),
                 host: "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".into(),
                 path: Some("/".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
-                    "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21",
+                    "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21/",
                 )
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("v1.0.0".into()),
+                version: Some(Version::Tag("v1.0.0".into())),
                 host: "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".into(),
                 path: Some("/".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
-                    "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21",
+                    "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21/",
                 )
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("latest".into()),
+                version: Some(Version::Latest),
                 host: "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".into(),
                 path: Some("/".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
-                    "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21",
+                    "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21/",
                 )
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("0.0.0.1".into()),
+                version: Some(Version::Tag("0.0.0.1".into())),
                 host: "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".into(),
                 path: Some("/path/to+file.txt".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21/path/to+file.txt",
@@ -409,9 +1115,11 @@ example.com/path/to+file.txt
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("1".into()),
+                version: Some(Version::Number(1)),
                 host: "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".into(),
                 path: Some("/path/to+file.txt".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21/path/to+file.txt",
@@ -419,9 +1127,11 @@ example.com/path/to+file.txt
                 .expect("Invalid test data"),
                 },
             DatUrl {
-                version: Some("c1".into()),
+                version: Some(Version::Content(1)),
                 host: "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".into(),
                 path: Some("/path/to+file.txt".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21/path/to+file.txt",
@@ -429,9 +1139,11 @@ example.com/path/to+file.txt
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("v1".into()),
+                version: Some(Version::Tag("v1".into())),
                 host: "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".into(),
                 path: Some("/path/to+file.txt".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21/path/to+file.txt",
@@ -439,9 +1151,11 @@ example.com/path/to+file.txt
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("v1.0.0".into()),
+                version: Some(Version::Tag("v1.0.0".into())),
                 host: "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".into(),
                 path: Some("/path/to+file.txt".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21/path/to+file.txt",
@@ -449,9 +1163,11 @@ example.com/path/to+file.txt
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("latest".into()),
+                version: Some(Version::Latest),
                 host: "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".into(),
                 path: Some("/path/to+file.txt".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21/path/to+file.txt",
@@ -459,62 +1175,74 @@ example.com/path/to+file.txt
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("0.0.0.1".into()),
+                version: Some(Version::Tag("0.0.0.1".into())),
                 host: "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".into(),
                 path: None,
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
-                    "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21/",
+                    "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21",
                 )
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("1".into()),
+                version: Some(Version::Number(1)),
                 host: "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".into(),
                 path: None,
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
-                    "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21/",
+                    "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21",
                 )
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("c1".into()),
+                version: Some(Version::Content(1)),
                 host: "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".into(),
                 path: None,
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
-                    "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21/",
+                    "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21",
                 )
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("v1".into()),
+                version: Some(Version::Tag("v1".into())),
                 host: "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".into(),
                 path: None,
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
-                    "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21/",
+                    "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21",
                 )
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("v1.0.0".into()),
+                version: Some(Version::Tag("v1.0.0".into())),
                 host: "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".into(),
                 path: None,
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
-                    "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21/",
+                    "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21",
                 )
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("latest".into()),
+                version: Some(Version::Latest),
                 host: "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".into(),
                 path: None,
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
-                    "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21/",
+                    "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21",
                 )
                 .expect("Invalid test data"),
             },
@@ -522,6 +1250,8 @@ example.com/path/to+file.txt
                 version: None,
                 host: "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".into(),
                 path: Some("/".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21/",
@@ -531,6 +1261,8 @@ example.com/path/to+file.txt
                 version: None,
                 host: "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".into(),
                 path: Some("/path/to+file.txt".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),url: Url::parse(
                     "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21/path/to+file.txt",
                 )
@@ -540,16 +1272,20 @@ example.com/path/to+file.txt
                 version: None,
                 host: "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".into(),
                 path: None,
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
-                    "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21/",
+                    "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21",
                 )
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("0.0.0.1".into()),
+                version: Some(Version::Tag("0.0.0.1".into())),
                 host: "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".into(),
                 path: Some("/".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21/",
@@ -557,9 +1293,11 @@ example.com/path/to+file.txt
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("1".into()),
+                version: Some(Version::Number(1)),
                 host: "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".into(),
                 path: Some("/".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21/",
@@ -567,9 +1305,11 @@ example.com/path/to+file.txt
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("c1".into()),
+                version: Some(Version::Content(1)),
                 host: "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".into(),
                 path: Some("/".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21/",
@@ -577,9 +1317,11 @@ example.com/path/to+file.txt
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("v1".into()),
+                version: Some(Version::Tag("v1".into())),
                 host: "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".into(),
                 path: Some("/".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21/",
@@ -587,9 +1329,11 @@ example.com/path/to+file.txt
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("v1.0.0".into()),
+                version: Some(Version::Tag("v1.0.0".into())),
                 host: "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".into(),
                 path: Some("/".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21/",
@@ -597,9 +1341,11 @@ example.com/path/to+file.txt
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("latest".into()),
+                version: Some(Version::Latest),
                 host: "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".into(),
                 path: Some("/".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21/",
@@ -607,9 +1353,11 @@ example.com/path/to+file.txt
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("0.0.0.1".into()),
+                version: Some(Version::Tag("0.0.0.1".into())),
                 host: "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".into(),
                 path: Some("/path/to+file.txt".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21/path/to+file.txt",
@@ -617,9 +1365,11 @@ example.com/path/to+file.txt
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("1".into()),
+                version: Some(Version::Number(1)),
                 host: "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".into(),
                 path: Some("/path/to+file.txt".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21/path/to+file.txt",
@@ -627,9 +1377,11 @@ example.com/path/to+file.txt
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("c1".into()),
+                version: Some(Version::Content(1)),
                 host: "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".into(),
                 path: Some("/path/to+file.txt".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21/path/to+file.txt",
@@ -637,9 +1389,11 @@ example.com/path/to+file.txt
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("v1".into()),
+                version: Some(Version::Tag("v1".into())),
                 host: "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".into(),
                 path: Some("/path/to+file.txt".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21/path/to+file.txt",
@@ -647,9 +1401,11 @@ example.com/path/to+file.txt
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("v1.0.0".into()),
+                version: Some(Version::Tag("v1.0.0".into())),
                 host: "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".into(),
                 path: Some("/path/to+file.txt".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21/path/to+file.txt",
@@ -657,9 +1413,11 @@ example.com/path/to+file.txt
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("latest".into()),
+                version: Some(Version::Latest),
                 host: "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".into(),
                 path: Some("/path/to+file.txt".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21/path/to+file.txt",
@@ -667,9 +1425,11 @@ example.com/path/to+file.txt
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("0.0.0.1".into()),
+                version: Some(Version::Tag("0.0.0.1".into())),
                 host: "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".into(),
                 path: None,
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21",
@@ -677,9 +1437,11 @@ example.com/path/to+file.txt
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("1".into()),
+                version: Some(Version::Number(1)),
                 host: "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".into(),
                 path: None,
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21",
@@ -688,9 +1450,11 @@ example.com/path/to+file.txt
 
             },
             DatUrl {
-                version: Some("c1".into()),
+                version: Some(Version::Content(1)),
                 host: "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".into(),
                 path: None,
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21",
@@ -699,9 +1463,11 @@ example.com/path/to+file.txt
 
             },
             DatUrl {
-                version: Some("v1".into()),
+                version: Some(Version::Tag("v1".into())),
                 host: "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".into(),
                 path: None,
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21",
@@ -709,9 +1475,11 @@ example.com/path/to+file.txt
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("v1.0.0".into()),
+                version: Some(Version::Tag("v1.0.0".into())),
                 host: "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".into(),
                 path: None,
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21",
@@ -719,9 +1487,11 @@ example.com/path/to+file.txt
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("latest".into()),
+                version: Some(Version::Latest),
                 host: "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".into(),
                 path: None,
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21",
@@ -732,9 +1502,11 @@ example.com/path/to+file.txt
                 version: None,
                 host: "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".into(),
                 path: Some("/".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
-                    "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21",
+                    "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21/",
                 )
                 .expect("Invalid test data"),
             },
@@ -742,6 +1514,8 @@ example.com/path/to+file.txt
                 version: None,
                 host: "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".into(),
                 path: None,
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21",
@@ -752,6 +1526,8 @@ example.com/path/to+file.txt
                 version: None,
                 host: "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".into(),
                 path: Some("/path/to+file.txt".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21/path/to+file.txt",
@@ -759,69 +1535,83 @@ example.com/path/to+file.txt
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("0.0.0.1".into()),
+                version: Some(Version::Tag("0.0.0.1".into())),
                 host: "example.com".into(),
                 path: Some("/".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
-                    "dat://example.com",
+                    "dat://example.com/",
                 )
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("1".into()),
+                version: Some(Version::Number(1)),
                 host: "example.com".into(),
                 path: Some("/".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
-                    "dat://example.com",
+                    "dat://example.com/",
                 )
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("c1".into()),
+                version: Some(Version::Content(1)),
                 host: "example.com".into(),
                 path: Some("/".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
-                    "dat://example.com",
+                    "dat://example.com/",
                 )
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("v1".into()),
+                version: Some(Version::Tag("v1".into())),
                 host: "example.com".into(),
                 path: Some("/".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
-                    "dat://example.com",
+                    "dat://example.com/",
                 )
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("v1.0.0".into()),
+                version: Some(Version::Tag("v1.0.0".into())),
                 host: "example.com".into(),
                 path: Some("/".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
-                    "dat://example.com",
+                    "dat://example.com/",
                 )
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("latest".into()),
+                version: Some(Version::Latest),
                 host: "example.com".into(),
                 path: Some("/".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
-                    "dat://example.com",
+                    "dat://example.com/",
                 )
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("0.0.0.1".into()),
+                version: Some(Version::Tag("0.0.0.1".into())),
                 host: "example.com".into(),
                 path: Some("/path/to+file.txt".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://example.com/path/to+file.txt",
@@ -829,9 +1619,11 @@ example.com/path/to+file.txt
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("1".into()),
+                version: Some(Version::Number(1)),
                 host: "example.com".into(),
                 path: Some("/path/to+file.txt".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://example.com/path/to+file.txt",
@@ -840,9 +1632,11 @@ example.com/path/to+file.txt
 
             },
             DatUrl {
-                version: Some("c1".into()),
+                version: Some(Version::Content(1)),
                 host: "example.com".into(),
                 path: Some("/path/to+file.txt".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://example.com/path/to+file.txt",
@@ -851,9 +1645,11 @@ example.com/path/to+file.txt
 
             },
             DatUrl {
-                version: Some("v1".into()),
+                version: Some(Version::Tag("v1".into())),
                 host: "example.com".into(),
                 path: Some("/path/to+file.txt".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://example.com/path/to+file.txt",
@@ -862,9 +1658,11 @@ example.com/path/to+file.txt
 
             },
             DatUrl {
-                version: Some("v1.0.0".into()),
+                version: Some(Version::Tag("v1.0.0".into())),
                 host: "example.com".into(),
                 path: Some("/path/to+file.txt".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://example.com/path/to+file.txt",
@@ -873,9 +1671,11 @@ example.com/path/to+file.txt
 
             },
             DatUrl {
-                version: Some("latest".into()),
+                version: Some(Version::Latest),
                 host: "example.com".into(),
                 path: Some("/path/to+file.txt".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://example.com/path/to+file.txt",
@@ -884,67 +1684,79 @@ example.com/path/to+file.txt
 
             },
             DatUrl {
-                version: Some("0.0.0.1".into()),
+                version: Some(Version::Tag("0.0.0.1".into())),
                 host: "example.com".into(),
                 path: None,
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
-                    "dat://example.com/",
+                    "dat://example.com",
                 )
                 .expect("Invalid test data"),
 
             },
             DatUrl {
-                version: Some("1".into()),
+                version: Some(Version::Number(1)),
                 host: "example.com".into(),
                 path: None,
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
-                    "dat://example.com/",
+                    "dat://example.com",
                 )
                 .expect("Invalid test data"),
 
             },
             DatUrl {
-                version: Some("c1".into()),
+                version: Some(Version::Content(1)),
                 host: "example.com".into(),
                 path: None,
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
-                    "dat://example.com/",
+                    "dat://example.com",
                 )
                 .expect("Invalid test data"),
 
             },
             DatUrl {
-                version: Some("v1".into()),
+                version: Some(Version::Tag("v1".into())),
                 host: "example.com".into(),
                 path: None,
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
-                    "dat://example.com/",
+                    "dat://example.com",
                 )
                 .expect("Invalid test data"),
 
             },
             DatUrl {
-                version: Some("v1.0.0".into()),
+                version: Some(Version::Tag("v1.0.0".into())),
                 host: "example.com".into(),
                 path: None,
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
-                    "dat://example.com/",
+                    "dat://example.com",
                 )
                 .expect("Invalid test data"),
 
             },
             DatUrl {
-                version: Some("latest".into()),
+                version: Some(Version::Latest),
                 host: "example.com".into(),
                 path: None,
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
-                    "dat://example.com/",
+                    "dat://example.com",
                 )
                 .expect("Invalid test data"),
 
@@ -953,6 +1765,8 @@ example.com/path/to+file.txt
                 version: None,
                 host: "example.com".into(),
                 path: Some("/".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://example.com/",
@@ -964,9 +1778,11 @@ example.com/path/to+file.txt
                 version: None,
                 host: "example.com".into(),
                 path: None,
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
-                    "dat://example.com/",
+                    "dat://example.com",
                 )
                 .expect("Invalid test data"),
 
@@ -975,6 +1791,8 @@ example.com/path/to+file.txt
                 version: None,
                 host: "example.com".into(),
                 path: Some("/path/to+file.txt".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://example.com/path/to+file.txt",
@@ -982,9 +1800,11 @@ example.com/path/to+file.txt
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("0.0.0.1".into()),
+                version: Some(Version::Tag("0.0.0.1".into())),
                 host: "example.com".into(),
                 path: Some("/".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://example.com/",
@@ -992,9 +1812,11 @@ example.com/path/to+file.txt
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("1".into()),
+                version: Some(Version::Number(1)),
                 host: "example.com".into(),
                 path: Some("/".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://example.com/",
@@ -1003,9 +1825,11 @@ example.com/path/to+file.txt
 
             },
             DatUrl {
-                version: Some("c1".into()),
+                version: Some(Version::Content(1)),
                 host: "example.com".into(),
                 path: Some("/".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://example.com/",
@@ -1014,9 +1838,11 @@ example.com/path/to+file.txt
 
             },
             DatUrl {
-                version: Some("v1".into()),
+                version: Some(Version::Tag("v1".into())),
                 host: "example.com".into(),
                 path: Some("/".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://example.com/",
@@ -1024,9 +1850,11 @@ example.com/path/to+file.txt
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("v1.0.0".into()),
+                version: Some(Version::Tag("v1.0.0".into())),
                 host: "example.com".into(),
                 path: Some("/".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://example.com/",
@@ -1034,9 +1862,11 @@ example.com/path/to+file.txt
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("latest".into()),
+                version: Some(Version::Latest),
                 host: "example.com".into(),
                 path: Some("/".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://example.com/",
@@ -1044,9 +1874,11 @@ example.com/path/to+file.txt
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("0.0.0.1".into()),
+                version: Some(Version::Tag("0.0.0.1".into())),
                 host: "example.com".into(),
                 path: Some("/path/to+file.txt".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://example.com/path/to+file.txt",
@@ -1054,9 +1886,11 @@ example.com/path/to+file.txt
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("1".into()),
+                version: Some(Version::Number(1)),
                 host: "example.com".into(),
                 path: Some("/path/to+file.txt".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://example.com/path/to+file.txt",
@@ -1064,9 +1898,11 @@ example.com/path/to+file.txt
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("c1".into()),
+                version: Some(Version::Content(1)),
                 host: "example.com".into(),
                 path: Some("/path/to+file.txt".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://example.com/path/to+file.txt",
@@ -1074,9 +1910,11 @@ example.com/path/to+file.txt
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("v1".into()),
+                version: Some(Version::Tag("v1".into())),
                 host: "example.com".into(),
                 path: Some("/path/to+file.txt".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://example.com/path/to+file.txt",
@@ -1084,9 +1922,11 @@ example.com/path/to+file.txt
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("v1.0.0".into()),
+                version: Some(Version::Tag("v1.0.0".into())),
                 host: "example.com".into(),
                 path: Some("/path/to+file.txt".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://example.com/path/to+file.txt",
@@ -1094,9 +1934,11 @@ example.com/path/to+file.txt
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("latest".into()),
+                version: Some(Version::Latest),
                 host: "example.com".into(),
                 path: Some("/path/to+file.txt".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://example.com/path/to+file.txt",
@@ -1104,62 +1946,74 @@ example.com/path/to+file.txt
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("0.0.0.1".into()),
+                version: Some(Version::Tag("0.0.0.1".into())),
                 host: "example.com".into(),
                 path: None,
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
-                    "dat://example.com/",
+                    "dat://example.com",
                 )
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("1".into()),
+                version: Some(Version::Number(1)),
                 host: "example.com".into(),
                 path: None,
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
-                    "dat://example.com/",
+                    "dat://example.com",
                 )
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("c1".into()),
+                version: Some(Version::Content(1)),
                 host: "example.com".into(),
                 path: None,
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
-                    "dat://example.com/",
+                    "dat://example.com",
                 )
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("v1".into()),
+                version: Some(Version::Tag("v1".into())),
                 host: "example.com".into(),
                 path: None,
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
-                    "dat://example.com/",
+                    "dat://example.com",
                 )
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("v1.0.0".into()),
+                version: Some(Version::Tag("v1.0.0".into())),
                 host: "example.com".into(),
                 path: None,
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
-                    "dat://example.com/",
+                    "dat://example.com",
                 )
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("latest".into()),
+                version: Some(Version::Latest),
                 host: "example.com".into(),
                 path: None,
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
-                    "dat://example.com/",
+                    "dat://example.com",
                 )
                 .expect("Invalid test data"),
             },
@@ -1167,6 +2021,8 @@ example.com/path/to+file.txt
                 version: None,
                 host: "example.com".into(),
                 path: Some("/".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://example.com/",
@@ -1177,9 +2033,11 @@ example.com/path/to+file.txt
                 version: None,
                 host: "example.com".into(),
                 path: None,
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
-                    "dat://example.com/",
+                    "dat://example.com",
                 )
                 .expect("Invalid test data"),
             },
@@ -1187,6 +2045,8 @@ example.com/path/to+file.txt
                 version: None,
                 host: "example.com".into(),
                 path: Some("/path/to+file.txt".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://example.com/path/to+file.txt",
@@ -1197,6 +2057,8 @@ example.com/path/to+file.txt
                 version: None,
                 host: "192.0.2.0".into(),
                 path: None,
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://192.0.2.0",
@@ -1204,9 +2066,11 @@ example.com/path/to+file.txt
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("v1".into()),
+                version: Some(Version::Tag("v1".into())),
                 host: "192.0.2.0".into(),
                 path: None,
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://192.0.2.0",
@@ -1214,9 +2078,11 @@ example.com/path/to+file.txt
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("0.0.0.1".into()),
+                version: Some(Version::Tag("0.0.0.1".into())),
                 host: "192.0.2.0".into(),
                 path: Some("/path/to+file.txt".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://192.0.2.0/path/to+file.txt",
@@ -1227,6 +2093,8 @@ example.com/path/to+file.txt
                 version: None,
                 host: "192.0.2.0".into(),
                 path: Some("/path/to+file.txt".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://192.0.2.0/path/to+file.txt",
@@ -1237,6 +2105,8 @@ example.com/path/to+file.txt
                 version: None,
                 host: "[2001:DB8::0]".into(),
                 path: None,
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://[2001:DB8::0]",
@@ -1244,21 +2114,75 @@ example.com/path/to+file.txt
                 .expect("Invalid test data"),
             },
             DatUrl {
-                version: Some("0.0.0.1".into()),
+                version: Some(Version::Tag("0.0.0.1".into())),
                 host: "[2001:DB8::0]".into(),
                 path: Some("/path/to+file.txt".into()),
+                query: None,
+                fragment: None,
                 scheme: "dat://".into(),
                 url: Url::parse(
                     "dat://[2001:DB8::0]/path/to+file.txt",
                 )
                 .expect("Invalid test data"),
             },
+            DatUrl {
+                version: Some(Version::Number(1)),
+                host: "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".into(),
+                path: Some("/path/to+file.txt".into()),
+                query: Some("x=1".into()),
+                fragment: Some("top".into()),
+                scheme: "dat://".into(),
+                url: Url::parse(
+                    "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21/path/to+file.txt?x=1#top",
+                )
+                .expect("Invalid test data"),
+            },
+            DatUrl {
+                version: Some(Version::Tag("v1.0.0".into())),
+                host: "example.com".into(),
+                path: None,
+                query: Some("x=1".into()),
+                fragment: None,
+                scheme: "dat://".into(),
+                url: Url::parse(
+                    "dat://example.com?x=1",
+                )
+                .expect("Invalid test data"),
+            },
+            DatUrl {
+                version: None,
+                host: "example.com".into(),
+                path: None,
+                query: None,
+                fragment: Some("top".into()),
+                scheme: "dat://".into(),
+                url: Url::parse(
+                    "dat://example.com#top",
+                )
+                .expect("Invalid test data"),
+            },
+            DatUrl {
+                version: None,
+                host: "example.com".into(),
+                path: Some("/".into()),
+                query: Some("".into()),
+                fragment: Some("".into()),
+                scheme: "dat://".into(),
+                url: Url::parse(
+                    "dat://example.com/?#",
+                )
+                .expect("Invalid test data"),
+            },
         ];
 
-        for (url, output) in inputs.lines().zip(outputs) {
+        for (url, output) in INPUTS.lines().zip(outputs) {
             assert_eq!(&DatUrl::parse(url)?, output);
+            assert_eq!(
+                url.trim_start_matches("dat://"),
+                output.to_string().trim_start_matches("dat://")
+            );
         }
-        assert_eq!(inputs.lines().count(), outputs.len());
+        assert_eq!(INPUTS.lines().count(), outputs.len());
         Ok(())
     }
 }
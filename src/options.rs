@@ -0,0 +1,96 @@
+use crate::{DatUrl, Error};
+
+/// Configuration for parsing a [DatUrl](crate::DatUrl), created with [DatUrl::options](crate::DatUrl::options).
+///
+/// It allows changing the scheme used when the input has none, restricting the accepted schemes,
+/// and requiring the url to point to an archive key without a version.
+/// The defaults behave like [DatUrl::parse](crate::DatUrl::parse).
+///
+/// # Example
+///
+/// ```rust
+/// use parse_dat_url::{DatUrl, Error};
+///
+/// let options = DatUrl::options()
+///     .default_scheme("hyper")
+///     .allowed_schemes(&["dat", "hyper"])
+///     .require_key(true)
+///     .forbid_version(true);
+///
+/// let dat_url = options.parse("584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21/index.html")?;
+/// assert!(dat_url.is_hyper());
+///
+/// assert_eq!(Err(Error::UnsupportedScheme), options.parse("https://example.com/"));
+/// assert_eq!(Err(Error::InvalidKey), options.parse("dat://example.com/"));
+/// # Ok::<(), parse_dat_url::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct ParseOptions<'o> {
+    pub(crate) default_scheme: &'o str,
+    pub(crate) allowed_schemes: Option<&'o [&'o str]>,
+    pub(crate) require_key: bool,
+    pub(crate) forbid_version: bool,
+}
+
+impl<'o> Default for ParseOptions<'o> {
+    fn default() -> Self {
+        ParseOptions {
+            default_scheme: "dat",
+            allowed_schemes: None,
+            require_key: false,
+            forbid_version: false,
+        }
+    }
+}
+
+impl<'o> ParseOptions<'o> {
+    /// Sets the scheme used when the input has none, such as `hyper`. Defaults to `dat`.
+    ///
+    /// A trailing `://` is accepted and ignored.
+    pub fn default_scheme(mut self, scheme: &'o str) -> Self {
+        self.default_scheme = scheme.trim_end_matches("://");
+        self
+    }
+
+    /// Restricts the schemes accepted, compared in any case, such as `&["dat", "hyper"]`. Defaults to accepting any scheme.
+    ///
+    /// Urls with other schemes fail with [Error::UnsupportedScheme](crate::Error::UnsupportedScheme).
+    /// The default scheme is also checked against the list.
+    pub fn allowed_schemes(mut self, schemes: &'o [&'o str]) -> Self {
+        self.allowed_schemes = Some(schemes);
+        self
+    }
+
+    /// Requires the host to be a 64 characters hexadecimal key, rejecting domain names and IP addresses. Defaults to `false`.
+    ///
+    /// Urls with other hosts fail with [Error::InvalidKey](crate::Error::InvalidKey).
+    pub fn require_key(mut self, require: bool) -> Self {
+        self.require_key = require;
+        self
+    }
+
+    /// Rejects urls with a version part. Defaults to `false`.
+    ///
+    /// Versioned urls fail with [Error::UnexpectedVersion](crate::Error::UnexpectedVersion).
+    pub fn forbid_version(mut self, forbid: bool) -> Self {
+        self.forbid_version = forbid;
+        self
+    }
+
+    /// Parses the url with the configured options. Returns a struct which makes reference to the `&str` passed, with the same lifetime.
+    #[inline]
+    pub fn parse<'a>(&self, url: &'a str) -> Result<DatUrl<'a>, Error> {
+        DatUrl::parse_with(url, self)
+    }
+
+    pub(crate) fn is_allowed(&self, scheme: &str) -> bool {
+        let scheme = scheme.trim_end_matches("://");
+        self.allowed_schemes.is_none_or(|allowed| {
+            allowed.iter().any(|candidate| {
+                candidate
+                    .trim_end_matches("://")
+                    .eq_ignore_ascii_case(scheme)
+            })
+        })
+    }
+}
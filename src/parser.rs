@@ -0,0 +1,86 @@
+//! Single pass splitter for the dat url grammar:
+//!
+//! ```text
+//! [scheme "://"] host ["+" version] [path] ["?" query] ["#" fragment]
+//! ```
+//!
+//! - `scheme` is one or more word characters (letters, digits or `_`).
+//! - `host` is one or more characters, up to the first `/`, `+`, `?` or `#`.
+//! - `version` is one or more characters, up to the first `/`, `?` or `#`.
+//!   A `+` not followed by a version is kept as part of the path.
+//! - `path` runs up to the first `?` or `#`, `query` up to the first `#`, and `fragment` to the end, without line breaks.
+//!
+//! If the input has a scheme, but the rest does not match, the input is matched again as if it had no scheme.
+
+/// Parts of a dat url, borrowed from the input. Separators such as `://`, `+`, `?` and `#` are kept out of the parts, except for the scheme.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub(crate) struct Parts<'a> {
+    pub(crate) scheme: Option<&'a str>,
+    pub(crate) host: &'a str,
+    pub(crate) version: Option<&'a str>,
+    pub(crate) path: &'a str,
+    pub(crate) query: Option<&'a str>,
+    pub(crate) fragment: Option<&'a str>,
+}
+
+/// Splits the input into its parts, or returns `None` if it does not match the grammar.
+pub(crate) fn split(url: &str) -> Option<Parts<'_>> {
+    scheme_len(url)
+        .and_then(|len| split_authority(&url[len..], Some(&url[..len])))
+        .or_else(|| split_authority(url, None))
+}
+
+fn scheme_len(url: &str) -> Option<usize> {
+    let name_len: usize = url
+        .chars()
+        .take_while(|c| c.is_alphanumeric() || *c == '_')
+        .map(char::len_utf8)
+        .sum();
+
+    if name_len > 0 && url[name_len..].starts_with("://") {
+        Some(name_len + 3)
+    } else {
+        None
+    }
+}
+
+fn split_authority<'a>(rest: &'a str, scheme: Option<&'a str>) -> Option<Parts<'a>> {
+    let host_len = rest.find(['/', '+', '?', '#']).unwrap_or(rest.len());
+    if host_len == 0 {
+        return None;
+    }
+    let (host, mut rest) = rest.split_at(host_len);
+
+    let mut version = None;
+    if let Some(after_plus) = rest.strip_prefix('+') {
+        let version_len = after_plus.find(['/', '?', '#']).unwrap_or(after_plus.len());
+        if version_len > 0 {
+            version = Some(&after_plus[..version_len]);
+            rest = &after_plus[version_len..];
+        }
+    }
+
+    let path_len = rest.find(['?', '#']).unwrap_or(rest.len());
+    let (path, mut rest) = rest.split_at(path_len);
+
+    let mut query = None;
+    if let Some(after_question) = rest.strip_prefix('?') {
+        let query_len = after_question.find('#').unwrap_or(after_question.len());
+        query = Some(&after_question[..query_len]);
+        rest = &after_question[query_len..];
+    }
+
+    let fragment = rest.strip_prefix('#');
+    if fragment.is_some_and(|fragment| fragment.contains('\n')) {
+        return None;
+    }
+
+    Some(Parts {
+        scheme,
+        host,
+        version,
+        path,
+        query,
+        fragment,
+    })
+}
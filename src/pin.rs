@@ -0,0 +1,87 @@
+use crate::{find_iter, parser, DatKey, Version};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+/// Rewrite of an unversioned link into a link pinned to a version, found by [pin_versions](crate::pin_versions).
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Pin<'t> {
+    /// Byte range of the link in the text.
+    pub range: Range<usize>,
+    /// The link, as written in the text.
+    pub original: &'t str,
+    /// The link with the `+version` inserted after the host.
+    pub pinned: String,
+}
+
+/// Finds the `dat://<key>` and `hyper://<key>` links without a version in the text, and pins them to the version returned for their key.
+///
+/// Links are rewritten by inserting `+version` right after the host, so every other byte of the link stays as written.
+/// Links which already have a version, point to a domain name, or are bare keys without a scheme are left alone,
+/// as are links whose key has no version, a version which does not [round trip](crate::Version::round_trips),
+/// or a version not supported by `hyper://` urls.
+///
+/// The pins are returned in order, to be reviewed, or applied with [apply_pins](crate::apply_pins).
+///
+/// # Example
+///
+/// ```rust
+/// use parse_dat_url::{apply_pins, pin_versions, Version};
+///
+/// let text = "Read dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21/Guide.md, or dat://example.com/.";
+/// let pins = pin_versions(text, |_| Some(Version::Number(5)));
+///
+/// assert_eq!(1, pins.len());
+/// assert_eq!(
+///     "Read dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21+5/Guide.md, or dat://example.com/.",
+///     apply_pins(text, &pins)
+/// );
+/// ```
+pub fn pin_versions<'t, 'v, F>(text: &'t str, mut version_of: F) -> Vec<Pin<'t>>
+where
+    F: FnMut(&DatKey) -> Option<Version<'v>>,
+{
+    find_iter(text)
+        .filter_map(|(range, dat_url)| {
+            if dat_url.version().is_some() {
+                return None;
+            }
+            let key = dat_url.key()?;
+            let original = &text[range.clone()];
+            let parts = parser::split(original)?;
+            let host_end = parts.scheme?.len() + parts.host.len();
+
+            let version = version_of(&key).filter(Version::round_trips)?;
+            if dat_url.is_hyper() && !version.is_hyper_compatible() {
+                return None;
+            }
+
+            let (head, tail) = original.split_at(host_end);
+            Some(Pin {
+                range,
+                original,
+                pinned: format!("{}+{}{}", head, version, tail),
+            })
+        })
+        .collect()
+}
+
+/// Returns the text with the pins applied, leaving every other byte untouched.
+///
+/// The pins must come from [pin_versions](crate::pin_versions) on the same text, or at least be sorted and not overlap.
+pub fn apply_pins(text: &str, pins: &[Pin<'_>]) -> String {
+    let extra: usize = pins
+        .iter()
+        .map(|pin| pin.pinned.len().saturating_sub(pin.original.len()))
+        .sum();
+    let mut rewritten = String::with_capacity(text.len() + extra);
+    let mut position = 0;
+    for pin in pins {
+        rewritten.push_str(&text[position..pin.range.start]);
+        rewritten.push_str(&pin.pinned);
+        position = pin.range.end;
+    }
+    rewritten.push_str(&text[position..]);
+    rewritten
+}
@@ -0,0 +1,232 @@
+//! Resolution of domain names into archive keys, such as `dat://example.com` into `dat://584f…a21`.
+//!
+//! Each source of keys implements the [Resolver] trait, which turns a domain into a [Resolution].
+//! [Resolver::resolve] then rewrites a [DatUrl](crate::DatUrl), keeping its scheme, version, path, query and fragment.
+//!
+//! The network is kept behind traits, such as [HttpTransport] and [DnsTransport], so resolution can be tested against local stand-ins.
+
+use crate::{DatHost, DatKey, DatUrl};
+use std::fmt;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use std::{fs, io, process};
+
+mod cache;
+mod chain;
+mod dns;
+mod hosts;
+mod tofu;
+mod well_known;
+
+pub use cache::CachedResolver;
+pub use chain::{Answer, ResolverChain};
+pub use dns::{DnsResolver, DnsTransport, TxtRecord};
+pub use hosts::{HostsResolver, HOSTS_ENV};
+pub use tofu::TofuResolver;
+pub use well_known::{HttpResponse, HttpTransport, WellKnownResolver};
+
+/// Longest TTL accepted from a source, 2^31 - 1 seconds, the maximum allowed for DNS records by [RFC 2181](https://tools.ietf.org/html/rfc2181#section-8).
+///
+/// Longer TTLs read from the network are lowered to it, so they cannot overflow time computations.
+pub const MAX_TTL: Duration = Duration::from_secs(0x7fff_ffff);
+
+/// Possible errors returned while resolving a domain name into a key.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ResolveError {
+    /// Correspond to a host which is not a domain name, such as an IP address, or a domain which cannot be used on a request.
+    InvalidDomain,
+    /// Correspond to a failure on the transport used, such as a refused connection. Contains the description of the failure.
+    Transport(String),
+    /// Correspond to an HTTP response without a `200 OK` status. Contains the status received.
+    HttpStatus(u16),
+    /// Correspond to a record which does not hold a key, such as a `/.well-known/dat` file not starting with a `dat://<key>` url,
+    /// or a `datkey=` TXT record with an invalid key.
+    MalformedRecord,
+    /// Correspond to a domain without any record on the source, such as a `datkey=` TXT record or a hosts file line.
+    MissingRecord,
+    /// Correspond to several records holding different keys for the same domain. Contains each key found.
+    ConflictingRecords(Vec<DatKey>),
+    /// Correspond to a domain resolving into another key than the one pinned by a [TofuResolver].
+    /// The change can be trusted with [TofuResolver::accept].
+    KeyChanged {
+        /// The key pinned for the domain.
+        old: DatKey,
+        /// The key the domain resolves into now.
+        new: DatKey,
+    },
+    /// Correspond to every source of a [ResolverChain] failing. Contains the name and the failure of each source, in order.
+    AllSourcesFailed(Vec<(String, ResolveError)>),
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ResolveError::InvalidDomain => write!(f, "host is not a resolvable domain name")?,
+            ResolveError::Transport(message) => write!(f, "transport failure: {}", message)?,
+            ResolveError::HttpStatus(status) => write!(f, "unexpected http status {}", status)?,
+            ResolveError::MalformedRecord => write!(f, "malformed dat record")?,
+            ResolveError::MissingRecord => write!(f, "no dat record found for domain")?,
+            ResolveError::ConflictingRecords(keys) => {
+                write!(f, "{} conflicting dat records found for domain", keys.len())?
+            }
+            ResolveError::KeyChanged { old, new } => {
+                write!(f, "domain key changed from {} to {}", old, new)?
+            }
+            ResolveError::AllSourcesFailed(failures) => {
+                write!(f, "every source failed")?;
+                for (index, (source, error)) in failures.iter().enumerate() {
+                    let separator = if index == 0 { ": " } else { "; " };
+                    write!(f, "{}{}: {}", separator, source, error)?;
+                }
+            }
+        };
+        Ok(())
+    }
+}
+
+impl std::error::Error for ResolveError {}
+
+impl ResolveError {
+    /// Returns `true` if the failure may go away by retrying, such as a transport failure, so it should not be cached.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            ResolveError::Transport(_) => true,
+            ResolveError::AllSourcesFailed(failures) => {
+                failures.iter().any(|(_, error)| error.is_transient())
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Key a domain name resolves into, alongside how long the answer can be cached, if the source tells.
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+pub struct Resolution {
+    /// The archive key of the domain.
+    pub key: DatKey,
+    /// How long the answer can be cached, such as the `TTL=` line of a `/.well-known/dat` file.
+    pub ttl: Option<Duration>,
+}
+
+/// A source of keys for domain names.
+///
+/// # Example
+///
+/// ```rust
+/// use parse_dat_url::resolve::{HttpResponse, Resolver, WellKnownResolver};
+/// use parse_dat_url::DatUrl;
+/// use url::Url;
+///
+/// let resolver = WellKnownResolver::new(|url: &Url| {
+///     assert_eq!("https://example.com/.well-known/dat", url.as_str());
+///     Ok(HttpResponse {
+///         status: 200,
+///         body: "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21\nTTL=3600\n".into(),
+///     })
+/// });
+///
+/// let dat_url = DatUrl::parse("dat://example.com+5/index.html")?;
+/// assert_eq!(
+///     "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21+5/index.html",
+///     resolver.resolve(&dat_url)?.to_string()
+/// );
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub trait Resolver {
+    /// Looks up the key of a domain name, given in lowercase.
+    fn lookup(&self, domain: &str) -> Result<Resolution, ResolveError>;
+
+    /// Returns the url with its domain name replaced by the key it resolves into, keeping the scheme, version, path, query and fragment.
+    ///
+    /// Urls which already point to a key are returned as they are.
+    /// Fails with [ResolveError::InvalidDomain] if the host is an IP address.
+    fn resolve<'a>(&self, dat_url: &DatUrl<'a>) -> Result<DatUrl<'a>, ResolveError> {
+        match domain_of(dat_url)? {
+            Some(domain) => Ok(with_key(dat_url, self.lookup(&domain)?.key)),
+            None => Ok(dat_url.clone()),
+        }
+    }
+
+    /// Returns a resolver trying this one first, and the fallback when this one fails, such as local overrides before network backends.
+    ///
+    /// The error of the fallback is returned when both fail.
+    /// A [ResolveError::KeyChanged] of this resolver is returned as is, without trying the fallback,
+    /// so a [TofuResolver] cannot be bypassed, as with a [ResolverChain].
+    fn or<R: Resolver>(self, fallback: R) -> Or<Self, R>
+    where
+        Self: Sized,
+    {
+        Or {
+            first: self,
+            fallback,
+        }
+    }
+}
+
+/// Resolver trying a first resolver, and a fallback when the first fails. Created with [Resolver::or].
+#[derive(Debug, Clone)]
+pub struct Or<A, B> {
+    first: A,
+    fallback: B,
+}
+
+impl<A: Resolver, B: Resolver> Resolver for Or<A, B> {
+    fn lookup(&self, domain: &str) -> Result<Resolution, ResolveError> {
+        match self.first.lookup(domain) {
+            Err(ResolveError::KeyChanged { old, new }) => {
+                Err(ResolveError::KeyChanged { old, new })
+            }
+            Err(_) => self.fallback.lookup(domain),
+            resolution => resolution,
+        }
+    }
+}
+
+/// Returns the domain of the url in lowercase, or `None` if the url already points to a key.
+fn domain_of(dat_url: &DatUrl<'_>) -> Result<Option<String>, ResolveError> {
+    match dat_url.dat_host() {
+        DatHost::Key(..) => Ok(None),
+        DatHost::Domain(domain, _) => Ok(Some(domain.to_ascii_lowercase())),
+        DatHost::Ipv4(..) | DatHost::Ipv6(..) => Err(ResolveError::InvalidDomain),
+    }
+}
+
+fn with_key<'a>(dat_url: &DatUrl<'a>, key: DatKey) -> DatUrl<'a> {
+    let mut resolved = dat_url.clone();
+    resolved
+        .set_host(&key.to_hex())
+        .expect("keys are valid hosts");
+    resolved
+}
+
+/// Writes the file next to the destination first, and then renames it, so concurrent readers never see a partial file.
+///
+/// The temporary file is named after the process and a counter, so concurrent writers never rename each other's files.
+pub(crate) fn write_atomically(path: &Path, contents: &[u8]) -> io::Result<()> {
+    static WRITES: AtomicUsize = AtomicUsize::new(0);
+
+    let mut name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no file name"))?
+        .to_os_string();
+    name.push(format!(
+        ".{}.{}.tmp",
+        process::id(),
+        WRITES.fetch_add(1, Ordering::Relaxed)
+    ));
+    let temporary = path.with_file_name(name);
+
+    let written = fs::write(&temporary, contents).and_then(|()| fs::rename(&temporary, path));
+    if written.is_err() {
+        let _ = fs::remove_file(&temporary);
+    }
+    written
+}
+
+impl<R: Resolver + ?Sized> Resolver for &R {
+    #[inline]
+    fn lookup(&self, domain: &str) -> Result<Resolution, ResolveError> {
+        (**self).lookup(domain)
+    }
+}
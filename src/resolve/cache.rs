@@ -0,0 +1,354 @@
+use super::{Resolution, ResolveError, Resolver, MAX_TTL};
+use crate::DatKey;
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, SystemTime};
+#[cfg(feature = "cache-file")]
+use std::{fs, io, path::Path, time::UNIX_EPOCH};
+
+/// Caches the answers of another [Resolver], honouring the TTL of each answer.
+///
+/// - Answers without a TTL are kept for the default TTL, one hour unless configured. No answer is kept longer than [MAX_TTL](super::MAX_TTL).
+/// - Failures are cached for the negative TTL, one minute unless configured, except for transport failures, which are retried,
+///   and [key changes](ResolveError::KeyChanged), which stay with the [TofuResolver](super::TofuResolver) until accepted.
+/// - Once the capacity is reached, the least recently used domain is evicted.
+///
+/// With the `cache-file` feature, the cache can be saved to and loaded from a JSON file, so separate processes share a warm cache.
+///
+/// # Example
+///
+/// ```rust
+/// use parse_dat_url::resolve::{CachedResolver, HttpResponse, Resolver, WellKnownResolver};
+/// use std::time::Duration;
+/// use url::Url;
+///
+/// let resolver = WellKnownResolver::new(|_: &Url| {
+///     Ok(HttpResponse {
+///         status: 200,
+///         body: "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".into(),
+///     })
+/// });
+/// let cached = CachedResolver::new(resolver)
+///     .default_ttl(Duration::from_secs(600))
+///     .negative_ttl(Duration::from_secs(30))
+///     .capacity(100);
+///
+/// let first = cached.lookup("example.com")?;
+/// let second = cached.lookup("example.com")?;
+/// assert_eq!(first.key, second.key);
+/// assert_eq!(1, cached.len());
+/// # Ok::<(), parse_dat_url::resolve::ResolveError>(())
+/// ```
+#[derive(Debug)]
+pub struct CachedResolver<R> {
+    resolver: R,
+    default_ttl: Duration,
+    negative_ttl: Duration,
+    capacity: usize,
+    state: Mutex<CacheState>,
+}
+
+#[derive(Debug, Default)]
+struct CacheState {
+    entries: HashMap<String, Entry>,
+    tick: u64,
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    outcome: Result<DatKey, ResolveError>,
+    expires: SystemTime,
+    used: u64,
+}
+
+impl<R: Resolver> CachedResolver<R> {
+    /// Creates a cache around the resolver.
+    pub fn new(resolver: R) -> Self {
+        CachedResolver {
+            resolver,
+            default_ttl: Duration::from_secs(60 * 60),
+            negative_ttl: Duration::from_secs(60),
+            capacity: 1024,
+            state: Mutex::new(CacheState::default()),
+        }
+    }
+
+    /// Sets how long answers without a TTL are kept. Defaults to one hour.
+    pub fn default_ttl(mut self, ttl: Duration) -> Self {
+        self.default_ttl = ttl;
+        self
+    }
+
+    /// Sets how long failures are kept. Defaults to one minute. A zero duration disables negative caching.
+    pub fn negative_ttl(mut self, ttl: Duration) -> Self {
+        self.negative_ttl = ttl;
+        self
+    }
+
+    /// Sets how many domains are kept before evicting the least recently used. Defaults to 1024.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Returns the number of domains cached, including expired entries not evicted yet.
+    pub fn len(&self) -> usize {
+        self.state().entries.len()
+    }
+
+    /// Returns `true` if no domain is cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes every cached domain.
+    pub fn clear(&self) {
+        self.state().entries.clear();
+    }
+
+    fn state(&self) -> MutexGuard<'_, CacheState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(feature = "cache-file")]
+impl<R: Resolver> CachedResolver<R> {
+    /// Loads the entries saved on a JSON file by [save](#method.save), skipping expired ones. A missing file is not an error.
+    ///
+    /// Fails with [io::ErrorKind::InvalidData] if the file is not a cache file.
+    pub fn load(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(error) => return Err(error),
+        };
+        let document: serde_json::Value = serde_json::from_str(&contents)?;
+        let entries = document["entries"]
+            .as_array()
+            .ok_or_else(|| invalid_data("missing entries"))?;
+
+        let now = SystemTime::now();
+        let mut state = self.state();
+        for value in entries.iter().filter(|value| !file::is_key_changed(value)) {
+            let (domain, entry) =
+                file::decode(value).ok_or_else(|| invalid_data("malformed entry"))?;
+            if entry.expires > now && !state.entries.contains_key(&domain) {
+                state.insert(domain, entry, self.capacity);
+            }
+        }
+        Ok(())
+    }
+
+    /// Saves the entries which have not expired to a JSON file, from the least to the most recently used.
+    ///
+    /// The file is written under a temporary name first, and then renamed, so concurrent readers never see a partial file.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let now = SystemTime::now();
+        let mut entries: Vec<_> = {
+            let state = self.state();
+            state
+                .entries
+                .iter()
+                .filter(|(_, entry)| entry.expires > now)
+                .map(|(domain, entry)| (domain.clone(), entry.clone()))
+                .collect()
+        };
+        entries.sort_by_key(|(_, entry)| entry.used);
+
+        let document = serde_json::json!({
+            "entries": entries
+                .iter()
+                .filter_map(|(domain, entry)| file::encode(domain, entry))
+                .collect::<Vec<_>>(),
+        });
+
+        super::write_atomically(path.as_ref(), &serde_json::to_vec_pretty(&document)?)
+    }
+}
+
+#[cfg(feature = "cache-file")]
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+impl<R: Resolver> Resolver for CachedResolver<R> {
+    fn lookup(&self, domain: &str) -> Result<Resolution, ResolveError> {
+        let now = SystemTime::now();
+        if let Some(cached) = self.state().get(domain, now) {
+            return cached;
+        }
+
+        let outcome = self.resolver.lookup(domain);
+        let ttl = match &outcome {
+            Ok(resolution) => resolution.ttl.unwrap_or(self.default_ttl),
+            Err(error) if error.is_transient() => Duration::ZERO,
+            Err(ResolveError::KeyChanged { .. }) => Duration::ZERO,
+            Err(_) => self.negative_ttl,
+        };
+        let expires = now.checked_add(ttl.min(MAX_TTL));
+        if let Some(expires) = expires.filter(|_| !ttl.is_zero() && self.capacity > 0) {
+            let entry = Entry {
+                outcome: outcome.clone().map(|resolution| resolution.key),
+                expires,
+                used: 0,
+            };
+            self.state().insert(domain.to_owned(), entry, self.capacity);
+        }
+        outcome
+    }
+}
+
+impl CacheState {
+    fn get(&mut self, domain: &str, now: SystemTime) -> Option<Result<Resolution, ResolveError>> {
+        let remaining = match self.entries.get(domain) {
+            Some(entry) => entry.expires.duration_since(now).ok(),
+            None => return None,
+        };
+        if remaining.is_none_or(|remaining| remaining.is_zero()) {
+            self.entries.remove(domain);
+            return None;
+        }
+
+        self.tick += 1;
+        let entry = self.entries.get_mut(domain)?;
+        entry.used = self.tick;
+        Some(entry.outcome.clone().map(|key| Resolution {
+            key,
+            ttl: remaining,
+        }))
+    }
+
+    fn insert(&mut self, domain: String, mut entry: Entry, capacity: usize) {
+        self.tick += 1;
+        entry.used = self.tick;
+        self.entries.insert(domain, entry);
+
+        while self.entries.len() > capacity {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.used)
+                .map(|(domain, _)| domain.clone());
+            match oldest {
+                Some(domain) => self.entries.remove(&domain),
+                None => break,
+            };
+        }
+    }
+}
+
+/// Encoding of cache entries as JSON objects, such as `{"domain": "example.com", "expires": 1700000000, "key": "584f…"}`.
+///
+/// Failures are stored as an `error` object instead of a `key`, holding the `kind` of failure alongside the data of the variant, if any.
+/// Files saved by earlier versions hold the kind as an `error` string instead, with the data of the variant on the entry itself; they are still read.
+#[cfg(feature = "cache-file")]
+mod file {
+    use super::{Entry, ResolveError, UNIX_EPOCH};
+    use crate::DatKey;
+    use serde_json::{json, Value};
+    use std::convert::TryFrom;
+    use std::time::Duration;
+
+    /// Returns `None` for [key changes](ResolveError::KeyChanged), which belong to the pins of a [TofuResolver](crate::resolve::TofuResolver).
+    pub(super) fn encode(domain: &str, entry: &Entry) -> Option<Value> {
+        let expires = entry
+            .expires
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |expires| expires.as_secs());
+        let mut value = json!({ "domain": domain, "expires": expires });
+        match &entry.outcome {
+            Ok(key) => value["key"] = json!(key.to_hex()),
+            Err(error) => value["error"] = encode_error(error)?,
+        }
+        Some(value)
+    }
+
+    fn encode_error(error: &ResolveError) -> Option<Value> {
+        let value = match error {
+            ResolveError::InvalidDomain => json!({ "kind": "invalid-domain" }),
+            ResolveError::Transport(message) => json!({ "kind": "transport", "message": message }),
+            ResolveError::HttpStatus(status) => json!({ "kind": "http-status", "status": status }),
+            ResolveError::MalformedRecord => json!({ "kind": "malformed-record" }),
+            ResolveError::MissingRecord => json!({ "kind": "missing-record" }),
+            ResolveError::ConflictingRecords(keys) => json!({
+                "kind": "conflicting-records",
+                "keys": keys.iter().map(DatKey::to_hex).collect::<Vec<_>>(),
+            }),
+            ResolveError::KeyChanged { .. } => return None,
+            ResolveError::AllSourcesFailed(failures) => json!({
+                "kind": "all-sources-failed",
+                "failures": failures
+                    .iter()
+                    .map(|(source, error)| Some(json!({ "source": source, "error": encode_error(error)? })))
+                    .collect::<Option<Vec<_>>>()?,
+            }),
+        };
+        Some(value)
+    }
+
+    /// Returns `true` for the key changes saved by earlier versions, which are skipped rather than rejected.
+    pub(super) fn is_key_changed(value: &Value) -> bool {
+        value["error"]["kind"] == "key-changed" || value["error"] == "key-changed"
+    }
+
+    pub(super) fn decode(value: &Value) -> Option<(String, Entry)> {
+        let domain = value["domain"].as_str()?.to_owned();
+        let expires = UNIX_EPOCH.checked_add(Duration::from_secs(value["expires"].as_u64()?))?;
+        let outcome = match (value["key"].as_str(), value.get("error")) {
+            (Some(key), None) => Ok(decode_key(key)?),
+            (None, Some(Value::String(kind))) => Err(decode_kind(kind, value)?),
+            (None, Some(error)) => Err(decode_error(error)?),
+            _ => return None,
+        };
+        Some((
+            domain,
+            Entry {
+                outcome,
+                expires,
+                used: 0,
+            },
+        ))
+    }
+
+    fn decode_error(value: &Value) -> Option<ResolveError> {
+        decode_kind(value["kind"].as_str()?, value)
+    }
+
+    /// Decodes a failure of the kind, reading the data of the variant from `value`.
+    fn decode_kind(kind: &str, value: &Value) -> Option<ResolveError> {
+        let error = match kind {
+            "invalid-domain" => ResolveError::InvalidDomain,
+            "transport" => ResolveError::Transport(value["message"].as_str()?.to_owned()),
+            "http-status" => {
+                ResolveError::HttpStatus(u16::try_from(value["status"].as_u64()?).ok()?)
+            }
+            "malformed-record" => ResolveError::MalformedRecord,
+            "missing-record" => ResolveError::MissingRecord,
+            "conflicting-records" => ResolveError::ConflictingRecords(
+                value["keys"]
+                    .as_array()?
+                    .iter()
+                    .map(|key| decode_key(key.as_str()?))
+                    .collect::<Option<_>>()?,
+            ),
+            "all-sources-failed" => ResolveError::AllSourcesFailed(
+                value["failures"]
+                    .as_array()?
+                    .iter()
+                    .map(|failure| {
+                        Some((
+                            failure["source"].as_str()?.to_owned(),
+                            decode_error(&failure["error"])?,
+                        ))
+                    })
+                    .collect::<Option<_>>()?,
+            ),
+            _ => return None,
+        };
+        Some(error)
+    }
+
+    fn decode_key(hex: &str) -> Option<DatKey> {
+        DatKey::from_hex(hex).ok()
+    }
+}
@@ -0,0 +1,116 @@
+use super::{domain_of, with_key, Resolution, ResolveError, Resolver};
+use crate::DatUrl;
+use std::fmt;
+
+/// Answer of a [ResolverChain], telling which source resolved the domain.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct Answer<'c> {
+    /// The name of the source which answered.
+    pub source: &'c str,
+    /// The answer of the source.
+    pub resolution: Resolution,
+}
+
+/// Ordered list of named sources, such as local overrides, a cache, `/.well-known/dat` files and DNS TXT records.
+///
+/// Sources are tried in order, and the first answer wins. When every source fails, the lookup fails with
+/// [ResolveError::AllSourcesFailed], holding the failure of each source, so the cause can be acted upon.
+///
+/// A [ResolveError::KeyChanged] stops the chain, and is returned as it is, so a pinned key is never bypassed by a later source.
+///
+/// # Example
+///
+/// ```rust
+/// use parse_dat_url::resolve::{HostsResolver, HttpResponse, ResolveError, ResolverChain, WellKnownResolver};
+/// use parse_dat_url::DatUrl;
+/// use url::Url;
+///
+/// let chain = ResolverChain::new()
+///     .source("hosts", HostsResolver::new())
+///     .source("well-known", WellKnownResolver::new(|_: &Url| {
+///         Ok(HttpResponse { status: 404, body: String::new() })
+///     }));
+///
+/// let dat_url = DatUrl::parse("dat://example.com/")?;
+/// assert_eq!(
+///     Err(ResolveError::AllSourcesFailed(vec![
+///         ("hosts".to_string(), ResolveError::MissingRecord),
+///         ("well-known".to_string(), ResolveError::HttpStatus(404)),
+///     ])),
+///     chain.resolve_with_source(&dat_url)
+/// );
+/// # Ok::<(), parse_dat_url::Error>(())
+/// ```
+#[derive(Default)]
+pub struct ResolverChain<'r> {
+    sources: Vec<(String, Box<dyn Resolver + 'r>)>,
+}
+
+impl<'r> ResolverChain<'r> {
+    /// Creates a chain without any source.
+    pub fn new() -> Self {
+        ResolverChain::default()
+    }
+
+    /// Appends a source, tried after the ones already added. The name is reported on answers and failures.
+    pub fn source(mut self, name: &str, resolver: impl Resolver + 'r) -> Self {
+        self.sources.push((name.to_owned(), Box::new(resolver)));
+        self
+    }
+
+    /// Returns the names of the sources, in order.
+    pub fn sources(&self) -> impl Iterator<Item = &str> {
+        self.sources.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Looks up the key of a domain name, telling which source answered.
+    pub fn answer(&self, domain: &str) -> Result<Answer<'_>, ResolveError> {
+        let mut failures = Vec::new();
+        for (name, resolver) in &self.sources {
+            match resolver.lookup(domain) {
+                Ok(resolution) => {
+                    return Ok(Answer {
+                        source: name,
+                        resolution,
+                    })
+                }
+                Err(error @ ResolveError::KeyChanged { .. }) => return Err(error),
+                Err(error) => failures.push((name.clone(), error)),
+            }
+        }
+        Err(ResolveError::AllSourcesFailed(failures))
+    }
+
+    /// Returns the url with its domain name replaced by the key it resolves into, alongside the name of the source which answered.
+    ///
+    /// Urls which already point to a key are returned as they are, without a source.
+    pub fn resolve_with_source<'a>(
+        &self,
+        dat_url: &DatUrl<'a>,
+    ) -> Result<(DatUrl<'a>, Option<&str>), ResolveError> {
+        match domain_of(dat_url)? {
+            Some(domain) => {
+                let answer = self.answer(&domain)?;
+                Ok((
+                    with_key(dat_url, answer.resolution.key),
+                    Some(answer.source),
+                ))
+            }
+            None => Ok((dat_url.clone(), None)),
+        }
+    }
+}
+
+impl<'r> fmt::Debug for ResolverChain<'r> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResolverChain")
+            .field("sources", &self.sources().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl<'r> Resolver for ResolverChain<'r> {
+    fn lookup(&self, domain: &str) -> Result<Resolution, ResolveError> {
+        self.answer(domain).map(|answer| answer.resolution)
+    }
+}
@@ -0,0 +1,90 @@
+use super::{Resolution, ResolveError, Resolver, MAX_TTL};
+use crate::DatKey;
+use std::time::Duration;
+
+const DATKEY_PREFIX: &str = "datkey=";
+
+/// A TXT record, as returned by a [DnsTransport].
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct TxtRecord {
+    /// The text of the record, with its character strings concatenated.
+    pub text: String,
+    /// The time to live of the record, if known.
+    pub ttl: Option<Duration>,
+}
+
+/// Queries TXT records for a [DnsResolver].
+///
+/// It is implemented for closures, so tests and applications can plug any DNS client, or a local mock server.
+/// Names without records should return an empty list, and failures should be reported with [ResolveError::Transport].
+pub trait DnsTransport {
+    /// Returns the TXT records of the domain name.
+    fn txt(&self, domain: &str) -> Result<Vec<TxtRecord>, ResolveError>;
+}
+
+impl<F> DnsTransport for F
+where
+    F: Fn(&str) -> Result<Vec<TxtRecord>, ResolveError>,
+{
+    #[inline]
+    fn txt(&self, domain: &str) -> Result<Vec<TxtRecord>, ResolveError> {
+        self(domain)
+    }
+}
+
+/// Resolves domain names with `datkey=<key>` TXT records, as described by [DEP-0005](https://www.datprotocol.com/deps/0005-dns/).
+///
+/// Other TXT records on the domain, such as SPF policies, are ignored.
+/// Several `datkey=` records are accepted as long as they hold the same key, and the shortest TTL among them is used,
+/// lowered to [MAX_TTL](super::MAX_TTL) if longer.
+///
+/// - Domains without a `datkey=` record fail with [ResolveError::MissingRecord].
+/// - A `datkey=` record without a 64 characters hexadecimal key fails with [ResolveError::MalformedRecord], even if other records are valid.
+/// - Records holding different keys fail with [ResolveError::ConflictingRecords].
+#[derive(Debug, Clone)]
+pub struct DnsResolver<T> {
+    transport: T,
+}
+
+impl<T: DnsTransport> DnsResolver<T> {
+    /// Creates a resolver performing queries with the transport.
+    pub fn new(transport: T) -> Self {
+        DnsResolver { transport }
+    }
+}
+
+impl<T: DnsTransport> Resolver for DnsResolver<T> {
+    fn lookup(&self, domain: &str) -> Result<Resolution, ResolveError> {
+        if domain.is_empty() || domain.parse::<std::net::IpAddr>().is_ok() {
+            return Err(ResolveError::InvalidDomain);
+        }
+
+        let mut keys: Vec<DatKey> = Vec::new();
+        let mut ttl: Option<Duration> = None;
+        for record in self.transport.txt(domain)? {
+            let text = record.text.trim();
+            let value = match text.get(..DATKEY_PREFIX.len()) {
+                Some(prefix) if prefix.eq_ignore_ascii_case(DATKEY_PREFIX) => {
+                    &text[DATKEY_PREFIX.len()..]
+                }
+                _ => continue,
+            };
+
+            let key = DatKey::from_hex(value.trim()).map_err(|_| ResolveError::MalformedRecord)?;
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+            ttl = match (ttl, record.ttl) {
+                (Some(current), Some(other)) => Some(current.min(other)),
+                (current, other) => current.or(other),
+            };
+        }
+
+        let ttl = ttl.map(|ttl| ttl.min(MAX_TTL));
+        match keys.len() {
+            0 => Err(ResolveError::MissingRecord),
+            1 => Ok(Resolution { key: keys[0], ttl }),
+            _ => Err(ResolveError::ConflictingRecords(keys)),
+        }
+    }
+}
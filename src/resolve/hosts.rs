@@ -0,0 +1,145 @@
+use super::{Resolution, ResolveError, Resolver};
+use crate::{DatKey, DatUrl};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+use std::{env, fs, io};
+
+/// Environment variable read by [HostsResolver::from_env], holding the path of a hosts file.
+pub const HOSTS_ENV: &str = "DAT_HOSTS";
+
+/// Resolves domain names from local overrides, such as a hosts file, without touching the network.
+///
+/// The file follows the `/etc/hosts` layout: each line holds a key, as hexadecimal or as a `dat://<key>` url,
+/// followed by the domain names it serves. Text after a `#` is a comment.
+///
+/// ```text
+/// # Staging points to the local test archive.
+/// 584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21 staging.example.com www.staging.example.com
+/// ```
+///
+/// Unknown domains fail with [ResolveError::MissingRecord], so the resolver is meant to be consulted before network backends,
+/// with [Resolver::or]. Answers have a zero TTL, so a [CachedResolver](super::CachedResolver) never keeps them.
+///
+/// # Example
+///
+/// ```rust
+/// use parse_dat_url::resolve::{HostsResolver, Resolver};
+/// use parse_dat_url::DatUrl;
+///
+/// let hosts = HostsResolver::parse("584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21 staging.example.com")?;
+///
+/// let dat_url = DatUrl::parse("dat://staging.example.com/index.html")?;
+/// assert_eq!(
+///     "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21/index.html",
+///     hosts.resolve(&dat_url)?.to_string()
+/// );
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct HostsResolver {
+    hosts: HashMap<String, DatKey>,
+}
+
+impl HostsResolver {
+    /// Creates a resolver without any override.
+    pub fn new() -> Self {
+        HostsResolver::default()
+    }
+
+    /// Parses the overrides from the text of a hosts file.
+    ///
+    /// Fails with [io::ErrorKind::InvalidData] on a line with an invalid key or without domain names,
+    /// or if a domain is mapped to several keys.
+    pub fn parse(text: &str) -> io::Result<Self> {
+        let mut resolver = HostsResolver::new();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default();
+            let mut fields = line.split_whitespace();
+            let key = match fields.next() {
+                Some(key) => parse_key(key).ok_or_else(|| invalid_line(index, "invalid key"))?,
+                None => continue,
+            };
+
+            let mut domains = fields.peekable();
+            if domains.peek().is_none() {
+                return Err(invalid_line(index, "missing domain names"));
+            }
+            for domain in domains {
+                let domain = domain.to_ascii_lowercase();
+                if resolver
+                    .hosts
+                    .get(&domain)
+                    .is_some_and(|known| *known != key)
+                {
+                    return Err(invalid_line(index, "domain mapped to several keys"));
+                }
+                resolver.hosts.insert(domain, key);
+            }
+        }
+        Ok(resolver)
+    }
+
+    /// Reads the overrides from a hosts file. See [parse](#method.parse).
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        HostsResolver::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Reads the overrides from the hosts file named by the `DAT_HOSTS` environment variable, or returns an empty resolver if it is not set.
+    pub fn from_env() -> io::Result<Self> {
+        match env::var_os(HOSTS_ENV) {
+            Some(path) if !path.is_empty() => HostsResolver::from_file(path),
+            _ => Ok(HostsResolver::new()),
+        }
+    }
+
+    /// Maps the domain name to the key, replacing any previous override.
+    pub fn insert(&mut self, domain: &str, key: DatKey) {
+        self.hosts.insert(domain.to_ascii_lowercase(), key);
+    }
+
+    pub(crate) fn entries(self) -> impl Iterator<Item = (String, DatKey)> {
+        self.hosts.into_iter()
+    }
+
+    /// Returns the number of domains overridden.
+    pub fn len(&self) -> usize {
+        self.hosts.len()
+    }
+
+    /// Returns `true` if no domain is overridden.
+    pub fn is_empty(&self) -> bool {
+        self.hosts.is_empty()
+    }
+}
+
+impl Resolver for HostsResolver {
+    fn lookup(&self, domain: &str) -> Result<Resolution, ResolveError> {
+        self.hosts
+            .get(&domain.to_ascii_lowercase())
+            .map(|key| Resolution {
+                key: *key,
+                ttl: Some(Duration::ZERO),
+            })
+            .ok_or(ResolveError::MissingRecord)
+    }
+}
+
+fn parse_key(field: &str) -> Option<DatKey> {
+    DatKey::from_hex(field).ok().or_else(|| {
+        DatUrl::options()
+            .allowed_schemes(&["dat", "hyper"])
+            .require_key(true)
+            .forbid_version(true)
+            .parse(field)
+            .ok()?
+            .key()
+    })
+}
+
+fn invalid_line(index: usize, message: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("line {}: {}", index + 1, message),
+    )
+}
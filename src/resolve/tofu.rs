@@ -0,0 +1,132 @@
+use super::{HostsResolver, Resolution, ResolveError, Resolver};
+use crate::DatKey;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::{fs, io};
+
+/// Pins the first key seen for each domain name, trust on first use, and reports later changes instead of following them.
+///
+/// Once a domain is pinned, answers of the wrapped resolver with another key fail with [ResolveError::KeyChanged],
+/// until the new key is explicitly trusted with [accept](#method.accept).
+///
+/// Pins can be saved to and loaded from a file, using the layout of [HostsResolver] files.
+///
+/// # Example
+///
+/// ```rust
+/// use parse_dat_url::resolve::{HostsResolver, ResolveError, Resolver, TofuResolver};
+///
+/// let old = "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".parse()?;
+/// let new = "b0c5c0ec5c7e7a5f5a4a3f6a8c1ee2b0b2e93b3e5e0a6c5d7b9c3c1c0e6b7a5d".parse()?;
+///
+/// let mut hosts = HostsResolver::new();
+/// hosts.insert("example.com", new);
+/// let tofu = TofuResolver::new(hosts).with_pins(vec![("example.com".to_string(), old)]);
+/// assert_eq!(Err(ResolveError::KeyChanged { old, new }), tofu.lookup("example.com"));
+///
+/// tofu.accept("example.com", new);
+/// assert_eq!(new, tofu.lookup("example.com")?.key);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug)]
+pub struct TofuResolver<R> {
+    resolver: R,
+    pins: Mutex<HashMap<String, DatKey>>,
+}
+
+impl<R: Resolver> TofuResolver<R> {
+    /// Creates a pinning layer around the resolver, without any pin.
+    pub fn new(resolver: R) -> Self {
+        TofuResolver {
+            resolver,
+            pins: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Adds the pins, such as the ones returned by [pins](#method.pins) on another instance.
+    pub fn with_pins<I>(self, pins: I) -> Self
+    where
+        I: IntoIterator<Item = (String, DatKey)>,
+    {
+        self.state().extend(
+            pins.into_iter()
+                .map(|(domain, key)| (domain.to_ascii_lowercase(), key)),
+        );
+        self
+    }
+
+    /// Returns the key pinned for the domain name, if any.
+    pub fn pinned(&self, domain: &str) -> Option<DatKey> {
+        self.state().get(&domain.to_ascii_lowercase()).copied()
+    }
+
+    /// Returns every pin, sorted by domain name.
+    pub fn pins(&self) -> Vec<(String, DatKey)> {
+        let mut pins: Vec<_> = self
+            .state()
+            .iter()
+            .map(|(domain, key)| (domain.clone(), *key))
+            .collect();
+        pins.sort();
+        pins
+    }
+
+    /// Trusts the key for the domain name, replacing the previous pin, such as the `new` key of a [ResolveError::KeyChanged].
+    pub fn accept(&self, domain: &str, key: DatKey) {
+        self.state().insert(domain.to_ascii_lowercase(), key);
+    }
+
+    /// Removes the pin of the domain name, so the next key seen is trusted.
+    pub fn forget(&self, domain: &str) -> Option<DatKey> {
+        self.state().remove(&domain.to_ascii_lowercase())
+    }
+
+    /// Loads the pins saved on a file by [save](#method.save), replacing the current pins of the same domains. A missing file is not an error.
+    pub fn load(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(error) => return Err(error),
+        };
+        let hosts = HostsResolver::parse(&text)?;
+        self.state().extend(hosts.entries());
+        Ok(())
+    }
+
+    /// Saves the pins to a file, one `<key> <domain>` line each.
+    ///
+    /// The file is written under a temporary name first, and then renamed, so concurrent readers never see a partial file.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut text = String::new();
+        for (domain, key) in self.pins() {
+            writeln!(text, "{} {}", key, domain).expect("writing to a string never fails");
+        }
+
+        super::write_atomically(path.as_ref(), text.as_bytes())
+    }
+
+    fn state(&self) -> MutexGuard<'_, HashMap<String, DatKey>> {
+        self.pins.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<R: Resolver> Resolver for TofuResolver<R> {
+    fn lookup(&self, domain: &str) -> Result<Resolution, ResolveError> {
+        let resolution = self.resolver.lookup(domain)?;
+        let domain = domain.to_ascii_lowercase();
+        let mut pins = self.state();
+        match pins.get(&domain) {
+            Some(old) if *old != resolution.key => Err(ResolveError::KeyChanged {
+                old: *old,
+                new: resolution.key,
+            }),
+            Some(_) => Ok(resolution),
+            None => {
+                pins.insert(domain, resolution.key);
+                Ok(resolution)
+            }
+        }
+    }
+}
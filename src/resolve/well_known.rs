@@ -0,0 +1,97 @@
+use super::{Resolution, ResolveError, Resolver, MAX_TTL};
+use crate::DatUrl;
+use std::time::Duration;
+use url::Url;
+
+/// Response of an HTTP request, as returned by an [HttpTransport].
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct HttpResponse {
+    /// The status code, such as `200`.
+    pub status: u16,
+    /// The body, decoded as text.
+    pub body: String,
+}
+
+/// Performs HTTP `GET` requests for a [WellKnownResolver].
+///
+/// It is implemented for closures, so tests and applications can plug any client, or a local stand-in server.
+/// Implementations should follow redirects, and report failures with [ResolveError::Transport].
+pub trait HttpTransport {
+    /// Fetches the url.
+    fn get(&self, url: &Url) -> Result<HttpResponse, ResolveError>;
+}
+
+impl<F> HttpTransport for F
+where
+    F: Fn(&Url) -> Result<HttpResponse, ResolveError>,
+{
+    #[inline]
+    fn get(&self, url: &Url) -> Result<HttpResponse, ResolveError> {
+        self(url)
+    }
+}
+
+/// Resolves domain names with the `https://<domain>/.well-known/dat` file, as described by [DEP-0005](https://www.datprotocol.com/deps/0005-dns/).
+///
+/// The file starts with a `dat://<key>` url, optionally followed by a `TTL=<seconds>` line:
+///
+/// ```text
+/// dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21
+/// TTL=3600
+/// ```
+///
+/// Responses without a `200 OK` status fail with [ResolveError::HttpStatus],
+/// and files not starting with a key url fail with [ResolveError::MalformedRecord]. A missing or malformed TTL is ignored,
+/// and a TTL longer than [MAX_TTL](super::MAX_TTL) is lowered to it.
+#[derive(Debug, Clone)]
+pub struct WellKnownResolver<T> {
+    transport: T,
+}
+
+impl<T: HttpTransport> WellKnownResolver<T> {
+    /// Creates a resolver performing requests with the transport.
+    pub fn new(transport: T) -> Self {
+        WellKnownResolver { transport }
+    }
+
+    fn parse(body: &str) -> Result<Resolution, ResolveError> {
+        let mut lines = body.lines().map(str::trim).filter(|line| !line.is_empty());
+        let key = lines
+            .next()
+            .and_then(|line| {
+                DatUrl::options()
+                    .allowed_schemes(&["dat", "hyper"])
+                    .require_key(true)
+                    .forbid_version(true)
+                    .parse(line)
+                    .ok()
+            })
+            .and_then(|dat_url| dat_url.key())
+            .ok_or(ResolveError::MalformedRecord)?;
+
+        let ttl = lines
+            .filter_map(|line| line.split_once('='))
+            .find(|(name, _)| name.trim().eq_ignore_ascii_case("ttl"))
+            .and_then(|(_, value)| value.trim().parse().ok())
+            .map(|seconds| Duration::from_secs(seconds).min(MAX_TTL));
+
+        Ok(Resolution { key, ttl })
+    }
+}
+
+impl<T: HttpTransport> Resolver for WellKnownResolver<T> {
+    fn lookup(&self, domain: &str) -> Result<Resolution, ResolveError> {
+        let mut url = Url::parse("https://localhost/.well-known/dat").expect("static url is valid");
+        url.set_host(Some(domain))
+            .map_err(|_| ResolveError::InvalidDomain)?;
+        if !matches!(url.host(), Some(url::Host::Domain(_))) {
+            return Err(ResolveError::InvalidDomain);
+        }
+
+        let response = self.transport.get(&url)?;
+        if response.status != 200 {
+            return Err(ResolveError::HttpStatus(response.status));
+        }
+        WellKnownResolver::<T>::parse(&response.body)
+    }
+}
@@ -1,7 +1,13 @@
-use crate::DatUrl;
+//! Serde support for [DatUrl](crate::DatUrl) and [DatKey](crate::DatKey), enabled by the `serde` feature.
+//!
+//! Both are written as strings, such as `"dat://584f…a21+5/index.html"`.
+//! The [structured] module writes urls as maps of their parts instead.
+
+use crate::{DatKey, DatUrl};
+use alloc::string::ToString;
+use core::fmt;
 use serde::de;
 use serde::ser;
-use std::fmt;
 
 impl<'a> ser::Serialize for DatUrl<'a> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -46,3 +52,206 @@ impl<'de: 'a, 'a> de::Deserialize<'de> for DatUrl<'a> {
         deserializer.deserialize_str(DatUrlVisitor)
     }
 }
+
+impl ser::Serialize for DatKey {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        serializer.serialize_str(&self.to_hex())
+    }
+}
+
+struct DatKeyVisitor;
+
+impl<'de> de::Visitor<'de> for DatKeyVisitor {
+    type Value = DatKey;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a 64 characters hexadecimal key")
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        DatKey::from_hex(value).map_err(de::Error::custom)
+    }
+}
+
+impl<'de> de::Deserialize<'de> for DatKey {
+    fn deserialize<D>(deserializer: D) -> Result<DatKey, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_str(DatKeyVisitor)
+    }
+}
+
+pub mod structured {
+    //! Representation of a [DatUrl](crate::DatUrl) as a map of its parts, to be used with `#[serde(with = "parse_dat_url::serde::structured")]`.
+    //!
+    //! The scheme is written without `://`, and the version, path, query and fragment are `null` when absent:
+    //!
+    //! ```text
+    //! { "scheme": "dat", "host": "584f…a21", "version": "5", "path": "/index.html", "query": null, "fragment": null }
+    //! ```
+    //!
+    //! When reading, the optional parts may also be missing. The parts are joined and parsed with [DatUrl::parse](crate::DatUrl::parse),
+    //! so the same rules apply, and reading fails if the parts do not come back unchanged, such as a host holding a `/`.
+    //!
+    //! # Example
+    //!
+    //! ```rust
+    //! use parse_dat_url::DatUrl;
+    //! use serde_derive::{Deserialize, Serialize};
+    //!
+    //! #[derive(Serialize, Deserialize)]
+    //! struct Visit {
+    //!     #[serde(with = "parse_dat_url::serde::structured")]
+    //!     url: DatUrl<'static>,
+    //!     count: u64,
+    //! }
+    //! ```
+
+    use crate::DatUrl;
+    use alloc::format;
+    use alloc::string::{String, ToString};
+    use core::fmt;
+    use serde::de;
+    use serde::ser::{self, SerializeStruct};
+
+    const FIELDS: &[&str] = &["scheme", "host", "version", "path", "query", "fragment"];
+
+    /// Writes the url as a map of its parts.
+    pub fn serialize<S>(dat_url: &DatUrl<'_>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        let mut state = serializer.serialize_struct("DatUrl", FIELDS.len())?;
+        state.serialize_field("scheme", dat_url.scheme().trim_end_matches("://"))?;
+        state.serialize_field("host", dat_url.host().as_ref())?;
+        state.serialize_field(
+            "version",
+            &dat_url.version().as_ref().map(ToString::to_string),
+        )?;
+        state.serialize_field("path", &dat_url.path().as_deref())?;
+        state.serialize_field("query", &dat_url.query().as_deref())?;
+        state.serialize_field("fragment", &dat_url.fragment().as_deref())?;
+        state.end()
+    }
+
+    /// Reads the url from a map of its parts, validated with [DatUrl::parse](crate::DatUrl::parse).
+    pub fn deserialize<'de, 'a, D>(deserializer: D) -> Result<DatUrl<'a>, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_struct("DatUrl", FIELDS, StructuredVisitor)
+    }
+
+    #[derive(Default)]
+    struct Parts {
+        scheme: Option<String>,
+        host: Option<String>,
+        version: Option<String>,
+        path: Option<String>,
+        query: Option<String>,
+        fragment: Option<String>,
+    }
+
+    impl Parts {
+        fn into_dat_url<E: de::Error>(self) -> Result<DatUrl<'static>, E> {
+            let scheme = self.scheme.ok_or_else(|| E::missing_field("scheme"))?;
+            let host = self.host.ok_or_else(|| E::missing_field("host"))?;
+
+            let mut url = format!("{}://{}", scheme, host);
+            if let Some(version) = &self.version {
+                url.push('+');
+                url.push_str(version);
+            }
+            if let Some(path) = &self.path {
+                url.push_str(path);
+            }
+            if let Some(query) = &self.query {
+                url.push('?');
+                url.push_str(query);
+            }
+            if let Some(fragment) = &self.fragment {
+                url.push('#');
+                url.push_str(fragment);
+            }
+
+            let dat_url = DatUrl::parse(&url).map_err(E::custom)?.into_owned();
+            let unchanged = dat_url.scheme().trim_end_matches("://") == scheme
+                && *dat_url.host() == *host
+                && dat_url.version().as_ref().map(ToString::to_string) == self.version
+                && dat_url.path().as_deref() == self.path.as_deref()
+                && dat_url.query().as_deref() == self.query.as_deref()
+                && dat_url.fragment().as_deref() == self.fragment.as_deref();
+            if unchanged {
+                Ok(dat_url)
+            } else {
+                Err(E::custom(format!(
+                    "parts do not form the dat url {}",
+                    dat_url
+                )))
+            }
+        }
+    }
+
+    struct StructuredVisitor;
+
+    impl<'de> de::Visitor<'de> for StructuredVisitor {
+        type Value = DatUrl<'static>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a map of dat url parts")
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+        where
+            A: de::MapAccess<'de>,
+        {
+            let mut parts = Parts::default();
+            let mut seen = [false; 6];
+            while let Some(name) = map.next_key::<String>()? {
+                let index = match FIELDS.iter().position(|field| *field == name) {
+                    Some(index) => index,
+                    None => {
+                        map.next_value::<de::IgnoredAny>()?;
+                        continue;
+                    }
+                };
+                if seen[index] {
+                    return Err(de::Error::duplicate_field(FIELDS[index]));
+                }
+                seen[index] = true;
+
+                match index {
+                    0 => parts.scheme = Some(map.next_value()?),
+                    1 => parts.host = Some(map.next_value()?),
+                    2 => parts.version = map.next_value()?,
+                    3 => parts.path = map.next_value()?,
+                    4 => parts.query = map.next_value()?,
+                    _ => parts.fragment = map.next_value()?,
+                }
+            }
+            parts.into_dat_url()
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: de::SeqAccess<'de>,
+        {
+            let mut parts = Parts::default();
+            let missing = |index: usize| de::Error::invalid_length(index, &self);
+            parts.scheme = Some(seq.next_element()?.ok_or_else(|| missing(0))?);
+            parts.host = Some(seq.next_element()?.ok_or_else(|| missing(1))?);
+            parts.version = seq.next_element()?.ok_or_else(|| missing(2))?;
+            parts.path = seq.next_element()?.ok_or_else(|| missing(3))?;
+            parts.query = seq.next_element()?.ok_or_else(|| missing(4))?;
+            parts.fragment = seq.next_element()?.ok_or_else(|| missing(5))?;
+            parts.into_dat_url()
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use crate::Error;
+use alloc::borrow::Cow;
+use alloc::string::ToString;
+use core::fmt;
+use core::str::FromStr;
+
+/// Typed representation of the version part of a dat url, the text after the `+` sign.
+///
+/// Versions follow the forms documented on [pfrazee/parse-dat-url](https://github.com/pfrazee/parse-dat-url):
+///
+/// - `+1`: a version number of the metadata feed
+/// - `+c1`: a checkout of the content feed
+/// - `+v1.0.0` or `+0.0.0.1`: a named tag
+/// - `+latest`: the latest version of the archive
+/// - `+preview`: the local preview of the archive, as used by [Beaker](https://beakerbrowser.com)
+///
+/// Urls using the `hyper://` scheme only accept version numbers and `+latest`.
+///
+/// # Example
+///
+/// ```rust
+/// use parse_dat_url::{DatUrl, Version};
+///
+/// let dat_url = DatUrl::parse("dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21+c12/")?;
+/// assert_eq!(&Some(Version::Content(12)), dat_url.version());
+/// # Ok::<(), parse_dat_url::Error>(())
+/// ```
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+pub enum Version<'a> {
+    /// Version number of the metadata feed, such as `+1`.
+    Number(u64),
+    /// Checkout of the content feed on the given version, such as `+c1`.
+    Content(u64),
+    /// Named tag, such as `+v1.0.0` or `+0.0.0.1`. Holds the text as written on the url.
+    Tag(Cow<'a, str>),
+    /// Latest version of the archive, `+latest`.
+    Latest,
+    /// Local preview of the archive, `+preview`.
+    Preview,
+}
+
+impl<'a> Version<'a> {
+    /// Parses the text after the `+` sign on a dat url. Returns a struct which makes reference to the `&str` passed, with the same lifetime.
+    ///
+    /// Versions starting with a digit must either be a plain number, without leading zeros, or a dotted tag such as `0.0.0.1`.
+    /// Versions starting with `c` followed by a digit must be a content feed checkout.
+    /// Anything else fails with [Error::InvalidVersion](crate::Error::InvalidVersion), such as `01x` or `c1x`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use parse_dat_url::{Error, Version};
+    ///
+    /// assert_eq!(Ok(Version::Number(1)), Version::parse("1"));
+    /// assert_eq!(Ok(Version::Tag("v1.0.0".into())), Version::parse("v1.0.0"));
+    /// assert_eq!(Err(Error::InvalidVersion), Version::parse("01x"));
+    /// ```
+    pub fn parse(version: &'a str) -> Result<Version<'a>, Error> {
+        match version {
+            "latest" => return Ok(Version::Latest),
+            "preview" => return Ok(Version::Preview),
+            _ => {}
+        }
+
+        let bytes = version.as_bytes();
+        match bytes.first() {
+            None => Err(Error::InvalidVersion),
+            Some(b'0'..=b'9') if Version::is_dotted(version) => Ok(Version::Tag(version.into())),
+            Some(b'0'..=b'9') => Version::parse_number(version).map(Version::Number),
+            Some(b'c') if bytes.get(1).is_some_and(u8::is_ascii_digit) => {
+                Version::parse_number(&version[1..]).map(Version::Content)
+            }
+            Some(_) => Ok(Version::Tag(version.into())),
+        }
+    }
+
+    fn is_dotted(version: &str) -> bool {
+        version.contains('.')
+            && version
+                .split('.')
+                .all(|segment| !segment.is_empty() && segment.bytes().all(|b| b.is_ascii_digit()))
+    }
+
+    fn parse_number(number: &str) -> Result<u64, Error> {
+        let leading_zero = number.len() > 1 && number.starts_with('0');
+        if leading_zero || !number.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(Error::InvalidVersion);
+        }
+        number.parse().map_err(|_| Error::InvalidVersion)
+    }
+
+    /// Returns true if the version is supported by `hyper://` urls, which are versioned by number or `+latest` only.
+    pub(crate) fn is_hyper_compatible(&self) -> bool {
+        matches!(self, Version::Number(_) | Version::Latest)
+    }
+
+    /// Returns true if the version reads back as itself once written after the `+` sign on a url.
+    ///
+    /// It is not the case for tags which [parse](#method.parse) reads as another version, such as `1x`, `latest` or an empty tag,
+    /// nor for tags containing one of `/`, `?`, `#` and `+`, which the url grammar splits on.
+    pub fn round_trips(&self) -> bool {
+        let text = self.to_string();
+        !text.contains(['/', '?', '#', '+'])
+            && Version::parse(&text).is_ok_and(|parsed| parsed == *self)
+    }
+
+    /// Converts a [Version](crate::Version) with a `'a` lifetime into a owned enum, with the `'static` lifetime.
+    pub fn into_owned(self) -> Version<'static> {
+        match self {
+            Version::Number(number) => Version::Number(number),
+            Version::Content(number) => Version::Content(number),
+            Version::Tag(tag) => Version::Tag(tag.into_owned().into()),
+            Version::Latest => Version::Latest,
+            Version::Preview => Version::Preview,
+        }
+    }
+}
+
+impl<'a> fmt::Display for Version<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Version::Number(number) => write!(f, "{}", number),
+            Version::Content(number) => write!(f, "c{}", number),
+            Version::Tag(tag) => write!(f, "{}", tag),
+            Version::Latest => write!(f, "latest"),
+            Version::Preview => write!(f, "preview"),
+        }
+    }
+}
+
+impl<'a> FromStr for Version<'a> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Version::parse(s).map(Version::into_owned)
+    }
+}
+
+impl<'a> core::convert::TryFrom<&'a str> for Version<'a> {
+    type Error = Error;
+
+    #[inline]
+    fn try_from(s: &'a str) -> Result<Self, Self::Error> {
+        Version::parse(s)
+    }
+}
@@ -0,0 +1,165 @@
+use parse_dat_url::Error as ParseError;
+use parse_dat_url::{DatKey, DatUrl, Version};
+use pretty_assertions::assert_eq;
+
+const KEY: &str = "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21";
+
+#[test]
+fn it_builds_the_same_url_as_parse() -> Result<(), ParseError> {
+    let key: DatKey = KEY.parse()?;
+    let built = DatUrl::builder()
+        .key(key)
+        .version(Version::Tag("v1.0.0".into()))?
+        .path_segments(["path", "to+file.txt"])
+        .build()?;
+
+    assert_eq!(
+        DatUrl::parse(&format!("dat://{}+v1.0.0/path/to+file.txt", KEY))?,
+        built
+    );
+    Ok(())
+}
+
+#[test]
+fn it_builds_domain_urls() -> Result<(), ParseError> {
+    let built = DatUrl::builder().domain("example.com")?.build()?;
+
+    assert_eq!(DatUrl::parse("dat://example.com")?, built);
+    assert_eq!(&None, built.path());
+    Ok(())
+}
+
+#[test]
+fn it_keeps_hosts_as_parse_does() -> Result<(), ParseError> {
+    for host in &[
+        "Example.COM",
+        "example.com:8080",
+        "192.0.2.0:3282",
+        "[2001:DB8::0]",
+    ] {
+        let built = DatUrl::builder().domain(host)?.path_segment("f").build()?;
+        let url = format!("dat://{}/f", host);
+        let parsed = DatUrl::parse(&url)?;
+        assert_eq!(parsed, built);
+        assert_eq!(parsed.dat_host(), built.dat_host());
+    }
+    Ok(())
+}
+
+#[test]
+fn it_sets_the_port() -> Result<(), ParseError> {
+    let built = DatUrl::builder().key(KEY.parse()?).port(3282).build()?;
+    assert_eq!(DatUrl::parse(&format!("dat://{}:3282", KEY))?, built);
+    assert_eq!(Some(3282), built.dat_host().port());
+
+    assert!(matches!(
+        DatUrl::builder()
+            .domain("example.com:8080")?
+            .port(3282)
+            .build(),
+        Err(ParseError::InvalidUrl(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn it_percent_encodes_the_parts() -> Result<(), ParseError> {
+    let built = DatUrl::builder()
+        .domain("example.com")?
+        .path_segment("a b")
+        .path_segment("c/d?#%")
+        .path_segment("")
+        .query_pair("q", "dat url")
+        .query_pair("&", "=")
+        .fragment("top section 50%")
+        .build()?;
+
+    assert_eq!(
+        "dat://example.com/a%20b/c%2Fd%3F%23%25/?q=dat+url&%26=%3D#top%20section%2050%25",
+        built.to_string()
+    );
+    assert_eq!(&Some("/a%20b/c%2Fd%3F%23%25/".into()), built.path());
+    assert_eq!(
+        vec![("q".into(), "dat url".into()), ("&".into(), "=".into())],
+        built
+            .query_pairs()
+            .into_owned()
+            .collect::<Vec<(String, String)>>()
+    );
+    assert_eq!(DatUrl::parse(&built.to_string())?, built);
+    Ok(())
+}
+
+#[test]
+fn it_checks_each_part() -> Result<(), ParseError> {
+    assert_eq!(
+        Err(ParseError::MissingHostname),
+        DatUrl::builder().path_segment("file.txt").build()
+    );
+    assert_eq!(
+        Some(ParseError::UnsupportedScheme),
+        DatUrl::builder().scheme("https").err()
+    );
+    assert_eq!(
+        Some(ParseError::InvalidUrl(
+            url::ParseError::InvalidDomainCharacter
+        )),
+        DatUrl::builder().domain("example.com+1").err()
+    );
+    assert!(DatUrl::builder().domain("exa mple.com").is_err());
+    assert_eq!(
+        Some(ParseError::MissingHostname),
+        DatUrl::builder().domain("").err()
+    );
+    assert_eq!(
+        Some(ParseError::InvalidVersion),
+        DatUrl::builder()
+            .scheme("hyper")?
+            .version(Version::Content(1))
+            .err()
+    );
+    assert_eq!(
+        Some(ParseError::InvalidVersion),
+        DatUrl::builder()
+            .version(Version::Preview)?
+            .scheme("hyper://")
+            .err()
+    );
+    Ok(())
+}
+
+#[test]
+fn it_rejects_versions_which_do_not_read_back() -> Result<(), ParseError> {
+    for tag in &["1x", "", "a/b", "a?b", "a#b", "a+b", "latest", "5", "c5"] {
+        assert_eq!(
+            Some(ParseError::InvalidVersion),
+            DatUrl::builder()
+                .domain("example.com")?
+                .version(Version::Tag((*tag).into()))
+                .err(),
+            "{:?}",
+            tag
+        );
+    }
+
+    let built = DatUrl::builder()
+        .domain("example.com")?
+        .version(Version::Tag("v1.0".into()))?
+        .path_segment("f")
+        .build()?;
+    assert_eq!(built, DatUrl::parse(&built.to_string())?);
+    Ok(())
+}
+
+#[test]
+fn it_builds_hyper_urls() -> Result<(), ParseError> {
+    let built = DatUrl::builder()
+        .scheme("hyper")?
+        .key(KEY.parse()?)
+        .version(Version::Latest)?
+        .build()?;
+
+    assert!(built.is_hyper());
+    assert_eq!(format!("hyper://{}+latest", KEY), built.to_string());
+    Ok(())
+}
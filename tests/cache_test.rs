@@ -0,0 +1,367 @@
+use parse_dat_url::resolve::{
+    CachedResolver, Resolution, ResolveError, Resolver, TofuResolver, MAX_TTL,
+};
+use pretty_assertions::assert_eq;
+use std::cell::RefCell;
+use std::thread;
+use std::time::Duration;
+
+const KEY: &str = "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21";
+
+/// Resolver answering from a fixed list, recording each lookup.
+#[derive(Default)]
+struct Recording {
+    lookups: RefCell<Vec<String>>,
+    ttl: Option<Duration>,
+}
+
+impl Recording {
+    fn with_ttl(ttl: Duration) -> Self {
+        Recording {
+            ttl: Some(ttl),
+            ..Recording::default()
+        }
+    }
+
+    fn count(&self, domain: &str) -> usize {
+        self.lookups
+            .borrow()
+            .iter()
+            .filter(|d| *d == domain)
+            .count()
+    }
+}
+
+impl Resolver for Recording {
+    fn lookup(&self, domain: &str) -> Result<Resolution, ResolveError> {
+        self.lookups.borrow_mut().push(domain.to_string());
+        match domain {
+            "missing.example.com" => Err(ResolveError::MissingRecord),
+            "offline.example.com" => Err(ResolveError::Transport("offline".to_string())),
+            _ => Ok(Resolution {
+                key: KEY.parse().expect("valid key"),
+                ttl: self.ttl,
+            }),
+        }
+    }
+}
+
+#[test]
+fn it_caches_answers_for_their_ttl() -> Result<(), ResolveError> {
+    let recording = Recording::with_ttl(Duration::from_secs(3600));
+    let cached = CachedResolver::new(&recording);
+
+    let first = cached.lookup("example.com")?;
+    let second = cached.lookup("example.com")?;
+
+    assert_eq!(1, recording.count("example.com"));
+    assert_eq!(first.key, second.key);
+    assert!(second
+        .ttl
+        .is_some_and(|ttl| ttl <= Duration::from_secs(3600)));
+    Ok(())
+}
+
+#[test]
+fn it_expires_answers() -> Result<(), ResolveError> {
+    let recording = Recording::with_ttl(Duration::from_millis(50));
+    let cached = CachedResolver::new(&recording);
+
+    cached.lookup("example.com")?;
+    thread::sleep(Duration::from_millis(100));
+    cached.lookup("example.com")?;
+
+    assert_eq!(2, recording.count("example.com"));
+    Ok(())
+}
+
+#[test]
+fn it_uses_the_default_ttl_without_one_from_the_source() -> Result<(), ResolveError> {
+    let recording = Recording::default();
+    let cached = CachedResolver::new(&recording).default_ttl(Duration::from_secs(600));
+    cached.lookup("example.com")?;
+    assert!(cached
+        .lookup("example.com")?
+        .ttl
+        .is_some_and(|ttl| ttl > Duration::from_secs(500)));
+    assert_eq!(1, recording.count("example.com"));
+
+    let recording = Recording::default();
+    let cached = CachedResolver::new(&recording).default_ttl(Duration::ZERO);
+    cached.lookup("example.com")?;
+    cached.lookup("example.com")?;
+    assert_eq!(2, recording.count("example.com"));
+    Ok(())
+}
+
+#[test]
+fn it_caches_failures_except_transport_ones() {
+    let recording = Recording::default();
+    let cached = CachedResolver::new(&recording);
+    for _ in 0..2 {
+        assert_eq!(
+            Err(ResolveError::MissingRecord),
+            cached.lookup("missing.example.com")
+        );
+        assert!(cached.lookup("offline.example.com").is_err());
+    }
+    assert_eq!(1, recording.count("missing.example.com"));
+    assert_eq!(2, recording.count("offline.example.com"));
+
+    let recording = Recording::default();
+    let cached = CachedResolver::new(&recording).negative_ttl(Duration::ZERO);
+    for _ in 0..2 {
+        assert!(cached.lookup("missing.example.com").is_err());
+    }
+    assert_eq!(2, recording.count("missing.example.com"));
+}
+
+#[test]
+fn it_does_not_cache_key_changes() -> Result<(), ResolveError> {
+    let old = "b0c5c0ec5c7e7a5f5a4a3f6a8c1ee2b0b2e93b3e5e0a6c5d7b9c3c1c0e6b7a5d"
+        .parse()
+        .expect("valid key");
+    let new = KEY.parse().expect("valid key");
+    let recording = Recording::default();
+    let tofu = TofuResolver::new(&recording).with_pins(vec![("example.com".to_string(), old)]);
+    let cached = CachedResolver::new(&tofu);
+
+    assert_eq!(
+        Err(ResolveError::KeyChanged { old, new }),
+        cached.lookup("example.com")
+    );
+    assert!(cached.is_empty());
+
+    tofu.accept("example.com", new);
+    assert_eq!(new, cached.lookup("example.com")?.key);
+    Ok(())
+}
+
+#[test]
+fn it_evicts_the_least_recently_used_domain() -> Result<(), ResolveError> {
+    let recording = Recording::default();
+    let cached = CachedResolver::new(&recording).capacity(2);
+
+    for domain in &[
+        "a.example.com",
+        "b.example.com",
+        "a.example.com",
+        "c.example.com",
+    ] {
+        cached.lookup(domain)?;
+    }
+    assert_eq!(2, cached.len());
+
+    cached.lookup("a.example.com")?;
+    cached.lookup("b.example.com")?;
+    assert_eq!(1, recording.count("a.example.com"));
+    assert_eq!(2, recording.count("b.example.com"));
+
+    cached.clear();
+    assert!(cached.is_empty());
+    Ok(())
+}
+
+#[test]
+fn it_caps_ttls_which_would_overflow() -> Result<(), ResolveError> {
+    let recording = Recording::with_ttl(Duration::from_secs(u64::MAX));
+    let cached = CachedResolver::new(&recording);
+
+    cached.lookup("example.com")?;
+    let second = cached.lookup("example.com")?;
+    assert_eq!(1, recording.count("example.com"));
+    assert!(second.ttl.is_some_and(|ttl| ttl <= MAX_TTL));
+
+    let recording = Recording::default();
+    let cached = CachedResolver::new(&recording)
+        .default_ttl(Duration::MAX)
+        .negative_ttl(Duration::MAX);
+    cached.lookup("example.com")?;
+    cached.lookup("missing.example.com").unwrap_err();
+    assert_eq!(2, cached.len());
+    Ok(())
+}
+
+#[cfg(feature = "cache-file")]
+#[test]
+fn it_persists_to_a_json_file() -> Result<(), Box<dyn std::error::Error>> {
+    let path =
+        std::env::temp_dir().join(format!("parse-dat-url-cache-{}.json", std::process::id()));
+
+    let recording = Recording::default();
+    let cached = CachedResolver::new(&recording);
+    cached.lookup("example.com")?;
+    cached.lookup("missing.example.com").unwrap_err();
+    cached.save(&path)?;
+
+    let recording = Recording::default();
+    let warm = CachedResolver::new(&recording);
+    warm.load(&path)?;
+    std::fs::remove_file(&path)?;
+
+    assert_eq!(
+        KEY.parse::<parse_dat_url::DatKey>()?,
+        warm.lookup("example.com")?.key
+    );
+    assert_eq!(
+        Err(ResolveError::MissingRecord),
+        warm.lookup("missing.example.com")
+    );
+    assert!(recording.lookups.borrow().is_empty());
+
+    warm.load(&path)?;
+    Ok(())
+}
+
+#[cfg(feature = "cache-file")]
+#[test]
+fn it_rejects_files_which_are_not_caches() -> Result<(), Box<dyn std::error::Error>> {
+    let path =
+        std::env::temp_dir().join(format!("parse-dat-url-invalid-{}.json", std::process::id()));
+    std::fs::write(&path, r#"{"entries": [{"domain": "example.com"}]}"#)?;
+
+    let cached = CachedResolver::new(Recording::default());
+    let error = cached.load(&path).unwrap_err();
+    std::fs::remove_file(&path)?;
+
+    assert_eq!(std::io::ErrorKind::InvalidData, error.kind());
+    Ok(())
+}
+
+#[cfg(feature = "cache-file")]
+#[test]
+fn it_loads_files_saved_with_error_strings() -> Result<(), Box<dyn std::error::Error>> {
+    let path = std::env::temp_dir().join(format!(
+        "parse-dat-url-previous-{}.json",
+        std::process::id()
+    ));
+    std::fs::write(
+        &path,
+        format!(
+            r#"{{"entries": [
+                {{"domain": "example.com", "expires": 4000000000, "key": "{0}"}},
+                {{"domain": "missing.example.com", "expires": 4000000000, "error": "missing-record"}},
+                {{"domain": "status.example.com", "expires": 4000000000, "error": "http-status", "status": 404}},
+                {{"domain": "conflict.example.com", "expires": 4000000000, "error": "conflicting-records", "keys": ["{0}", "{0}"]}},
+                {{"domain": "changed.example.com", "expires": 4000000000, "error": "key-changed", "old": "{0}", "new": "{0}"}}
+            ]}}"#,
+            KEY
+        ),
+    )?;
+
+    let recording = Recording::default();
+    let cached = CachedResolver::new(&recording);
+    cached.load(&path)?;
+    std::fs::remove_file(&path)?;
+
+    let key = KEY.parse()?;
+    assert_eq!(4, cached.len());
+    assert_eq!(key, cached.lookup("example.com")?.key);
+    assert_eq!(
+        Err(ResolveError::MissingRecord),
+        cached.lookup("missing.example.com")
+    );
+    assert_eq!(
+        Err(ResolveError::HttpStatus(404)),
+        cached.lookup("status.example.com")
+    );
+    assert_eq!(
+        Err(ResolveError::ConflictingRecords(vec![key, key])),
+        cached.lookup("conflict.example.com")
+    );
+    assert!(recording.lookups.borrow().is_empty());
+    Ok(())
+}
+
+#[cfg(feature = "cache-file")]
+#[test]
+fn it_skips_key_changes_saved_by_earlier_versions() -> Result<(), Box<dyn std::error::Error>> {
+    let path = std::env::temp_dir().join(format!(
+        "parse-dat-url-key-changed-{}.json",
+        std::process::id()
+    ));
+    std::fs::write(
+        &path,
+        format!(
+            r#"{{"entries": [
+                {{"domain": "example.com", "expires": 4000000000, "error": {{"kind": "key-changed", "old": "{0}", "new": "{0}"}}}},
+                {{"domain": "missing.example.com", "expires": 4000000000, "error": {{"kind": "missing-record"}}}}
+            ]}}"#,
+            KEY
+        ),
+    )?;
+
+    let recording = Recording::default();
+    let cached = CachedResolver::new(&recording);
+    cached.load(&path)?;
+    std::fs::remove_file(&path)?;
+
+    assert_eq!(1, cached.len());
+    assert!(cached.lookup("example.com").is_ok());
+    assert_eq!(1, recording.count("example.com"));
+    Ok(())
+}
+
+#[cfg(feature = "cache-file")]
+#[test]
+fn it_rejects_expiries_which_overflow() -> Result<(), Box<dyn std::error::Error>> {
+    let path = std::env::temp_dir().join(format!(
+        "parse-dat-url-overflow-{}.json",
+        std::process::id()
+    ));
+    std::fs::write(
+        &path,
+        format!(
+            r#"{{"entries": [{{"domain": "example.com", "expires": {}, "key": "{}"}}]}}"#,
+            u64::MAX,
+            KEY
+        ),
+    )?;
+
+    let cached = CachedResolver::new(Recording::default());
+    let error = cached.load(&path).unwrap_err();
+    std::fs::remove_file(&path)?;
+
+    assert_eq!(std::io::ErrorKind::InvalidData, error.kind());
+    Ok(())
+}
+
+#[cfg(feature = "cache-file")]
+#[test]
+fn it_saves_concurrently_without_partial_files() -> Result<(), Box<dyn std::error::Error>> {
+    struct Fixed;
+    impl Resolver for Fixed {
+        fn lookup(&self, _: &str) -> Result<Resolution, ResolveError> {
+            Ok(Resolution {
+                key: KEY.parse().expect("valid key"),
+                ttl: None,
+            })
+        }
+    }
+
+    let directory =
+        std::env::temp_dir().join(format!("parse-dat-url-concurrent-{}", std::process::id()));
+    std::fs::create_dir_all(&directory)?;
+    let path = directory.join("cache.json");
+
+    let cached = CachedResolver::new(Fixed);
+    cached.lookup("example.com")?;
+    thread::scope(|scope| {
+        for _ in 0..8 {
+            scope.spawn(|| {
+                for _ in 0..20 {
+                    cached.save(&path).expect("concurrent saves succeed");
+                }
+            });
+        }
+    });
+
+    let files: Vec<_> = std::fs::read_dir(&directory)?.collect::<Result<_, _>>()?;
+    let warm = CachedResolver::new(Fixed);
+    warm.load(&path)?;
+    std::fs::remove_dir_all(&directory)?;
+
+    assert_eq!(1, files.len());
+    assert_eq!(1, warm.len());
+    Ok(())
+}
@@ -0,0 +1,133 @@
+use parse_dat_url::resolve::{
+    CachedResolver, DnsResolver, HostsResolver, HttpResponse, ResolveError, Resolver,
+    ResolverChain, TofuResolver, TxtRecord, WellKnownResolver,
+};
+use parse_dat_url::{DatKey, DatUrl};
+use pretty_assertions::assert_eq;
+use url::Url;
+
+const KEY: &str = "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21";
+const OTHER_KEY: &str = "b0c5c0ec5c7e7a5f5a4a3f6a8c1ee2b0b2e93b3e5e0a6c5d7b9c3c1c0e6b7a5d";
+
+fn well_known(url: &Url) -> Result<HttpResponse, ResolveError> {
+    match url.host_str() {
+        Some("web.example.com") => Ok(HttpResponse {
+            status: 200,
+            body: format!("dat://{}\nTTL=60", KEY),
+        }),
+        Some("offline.example.com") => {
+            Err(ResolveError::Transport("connection refused".to_string()))
+        }
+        _ => Ok(HttpResponse {
+            status: 404,
+            body: String::new(),
+        }),
+    }
+}
+
+fn dns(domain: &str) -> Result<Vec<TxtRecord>, ResolveError> {
+    let text = match domain {
+        "dns.example.com" | "web.example.com" => format!("datkey={}", OTHER_KEY),
+        _ => return Ok(Vec::new()),
+    };
+    Ok(vec![TxtRecord { text, ttl: None }])
+}
+
+fn chain(hosts: HostsResolver) -> ResolverChain<'static> {
+    ResolverChain::new()
+        .source("hosts", hosts)
+        .source(
+            "well-known",
+            CachedResolver::new(WellKnownResolver::new(well_known)),
+        )
+        .source("dns", DnsResolver::new(dns))
+}
+
+#[test]
+fn it_reports_which_source_answered() -> Result<(), Box<dyn std::error::Error>> {
+    let mut hosts = HostsResolver::new();
+    hosts.insert("staging.example.com", OTHER_KEY.parse()?);
+    let chain = chain(hosts);
+    assert_eq!(
+        vec!["hosts", "well-known", "dns"],
+        chain.sources().collect::<Vec<_>>()
+    );
+
+    let answer = chain.answer("staging.example.com")?;
+    assert_eq!(
+        ("hosts", OTHER_KEY),
+        (answer.source, answer.resolution.key.to_hex().as_str())
+    );
+
+    let answer = chain.answer("web.example.com")?;
+    assert_eq!(
+        ("well-known", KEY),
+        (answer.source, answer.resolution.key.to_hex().as_str())
+    );
+
+    let dat_url = DatUrl::parse("dat://dns.example.com+5/index.html")?;
+    let (resolved, source) = chain.resolve_with_source(&dat_url)?;
+    assert_eq!(Some("dns"), source);
+    assert_eq!(
+        format!("dat://{}+5/index.html", OTHER_KEY),
+        resolved.to_string()
+    );
+
+    let dat_url = DatUrl::parse(&format!("dat://{}/", KEY))?.into_owned();
+    assert_eq!(
+        (dat_url.clone(), None),
+        chain.resolve_with_source(&dat_url)?
+    );
+    Ok(())
+}
+
+#[test]
+fn it_reports_every_failure() -> Result<(), Box<dyn std::error::Error>> {
+    let chain = chain(HostsResolver::new());
+    let dat_url = DatUrl::parse("dat://offline.example.com/")?;
+
+    let error = chain.resolve(&dat_url).unwrap_err();
+    assert_eq!(
+        ResolveError::AllSourcesFailed(vec![
+            ("hosts".to_string(), ResolveError::MissingRecord),
+            (
+                "well-known".to_string(),
+                ResolveError::Transport("connection refused".to_string())
+            ),
+            ("dns".to_string(), ResolveError::MissingRecord),
+        ]),
+        error
+    );
+    assert!(error.is_transient());
+    assert_eq!(
+        "every source failed: hosts: no dat record found for domain; \
+         well-known: transport failure: connection refused; \
+         dns: no dat record found for domain",
+        error.to_string()
+    );
+
+    assert_eq!(
+        Err(ResolveError::AllSourcesFailed(Vec::new())),
+        ResolverChain::new().lookup("example.com")
+    );
+    Ok(())
+}
+
+#[test]
+fn it_stops_on_key_changes() -> Result<(), Box<dyn std::error::Error>> {
+    let old: DatKey = OTHER_KEY.parse()?;
+    let new: DatKey = KEY.parse()?;
+    let chain = ResolverChain::new()
+        .source(
+            "well-known",
+            TofuResolver::new(WellKnownResolver::new(well_known))
+                .with_pins(vec![("web.example.com".to_string(), old)]),
+        )
+        .source("dns", DnsResolver::new(dns));
+
+    assert_eq!(
+        Err(ResolveError::KeyChanged { old, new }),
+        chain.lookup("web.example.com")
+    );
+    Ok(())
+}
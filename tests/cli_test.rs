@@ -0,0 +1,293 @@
+use pretty_assertions::assert_eq;
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+const KEY: &str = "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21";
+
+fn run(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_parse-dat-url"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("spawn parse-dat-url");
+    child
+        .stdin
+        .take()
+        .expect("stdin")
+        .write_all(stdin.as_bytes())
+        .expect("write stdin");
+    child.wait_with_output().expect("wait parse-dat-url")
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn it_prints_a_table() {
+    let url = format!("dat://{}+5/index.html?lang=en#top", KEY);
+    let output = run(&[&url], "");
+
+    assert!(output.status.success());
+    assert_eq!(
+        format!(
+            "url       {url}\n\
+             scheme    dat\n\
+             host      {key}\n\
+             host kind key\n\
+             key       {key}\n\
+             version   5 (number)\n\
+             path      /index.html\n\
+             query     lang=en\n\
+             fragment  top\n",
+            url = url,
+            key = KEY
+        ),
+        stdout(&output)
+    );
+}
+
+#[test]
+fn it_prints_the_key_of_hosts_with_a_port() {
+    let url = format!("dat://{}:3282/", KEY);
+    let output = run(&[&url], "");
+
+    assert!(output.status.success());
+    assert!(stdout(&output).contains(&format!(
+        "host      {}:3282\nhost kind key\nkey       {}\n",
+        KEY, KEY
+    )));
+}
+
+#[test]
+fn it_prints_json_lines_from_stdin() {
+    let output = run(&["--json"], "hyper://example.com+latest/\n\n192.0.2.0\n");
+    assert!(output.status.success());
+
+    let lines: Vec<serde_json::Value> = stdout(&output)
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("json line"))
+        .collect();
+    assert_eq!(2, lines.len());
+    assert_eq!("hyper", lines[0]["scheme"]);
+    assert_eq!("domain", lines[0]["host_kind"]);
+    assert_eq!("latest", lines[0]["version_kind"]);
+    assert_eq!(serde_json::Value::Null, lines[0]["key"]);
+    assert_eq!("ipv4", lines[1]["host_kind"]);
+    assert_eq!("dat://192.0.2.0", lines[1]["url"]);
+}
+
+#[test]
+fn it_fails_with_the_error_variant() {
+    let output = run(&["dat://example.com+01x", "example.com"], "");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("error: dat://example.com+01x: InvalidVersion"));
+    assert!(stdout(&output).contains("host      example.com"));
+
+    let output = run(&["--json", "+5"], "");
+    assert!(!output.status.success());
+    let line: serde_json::Value = serde_json::from_str(stdout(&output).trim()).expect("json line");
+    assert_eq!("InvalidRegex", line["error"]);
+    assert_eq!("+5", line["input"]);
+}
+
+/// Creates a directory tree with documents linking to archives, removed when dropped.
+struct Tree(std::path::PathBuf);
+
+impl Tree {
+    fn new(name: &str) -> Self {
+        let root =
+            std::env::temp_dir().join(format!("parse-dat-url-{}-{}", name, std::process::id()));
+        let files = [
+            (
+                "README.md",
+                format!("# Docs\n\nSee [the archive](dat://{}+5/index.html).\n", KEY),
+            ),
+            (
+                "docs/guide.md",
+                "Intro\n  mirror: hyper://example.com/guide, or not\n".to_string(),
+            ),
+            ("docs/notes.txt", format!("key {}\n", KEY)),
+            ("target/build.md", "dat://example.com/ignored\n".to_string()),
+        ];
+        for (path, contents) in &files {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().expect("parent")).expect("create dir");
+            std::fs::write(path, contents).expect("write file");
+        }
+        std::fs::write(root.join("binary.md"), [0xff, 0xfe, b'd']).expect("write file");
+        Tree(root)
+    }
+
+    fn path(&self) -> &str {
+        self.0.to_str().expect("utf-8 path")
+    }
+}
+
+impl Drop for Tree {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+#[test]
+fn it_extracts_links_from_directories() {
+    let tree = Tree::new("extract");
+    let output = run(
+        &[
+            "extract",
+            "--include",
+            "*.md",
+            "--exclude",
+            "target",
+            tree.path(),
+        ],
+        "",
+    );
+
+    assert!(output.status.success());
+    assert_eq!(
+        format!(
+            "{root}/README.md:3:19: dat://{key}+5/index.html\n\
+             {root}/docs/guide.md:2:11: hyper://example.com/guide\n",
+            root = tree.path(),
+            key = KEY
+        ),
+        stdout(&output)
+    );
+}
+
+#[test]
+fn it_extracts_links_as_json_lines() {
+    let tree = Tree::new("extract-json");
+    let notes = format!("{}/docs/notes.txt", tree.path());
+    let output = run(&["extract", "--json", &notes], "");
+
+    assert!(output.status.success());
+    let line: serde_json::Value = serde_json::from_str(stdout(&output).trim()).expect("json line");
+    assert_eq!(notes.as_str(), line["file"]);
+    assert_eq!(1, line["line"]);
+    assert_eq!(5, line["column"]);
+    assert_eq!(KEY, line["text"]);
+    assert_eq!(KEY, line["key"]);
+    assert_eq!(format!("dat://{}", KEY).as_str(), line["url"]);
+}
+
+#[test]
+fn it_reports_unreadable_paths() {
+    let output = run(&["extract", "/nonexistent/parse-dat-url"], "");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("/nonexistent/parse-dat-url"));
+}
+
+fn write_pin_fixtures(tree: &Tree) -> (String, String, String) {
+    let versions = format!("{}/versions.txt", tree.path());
+    std::fs::write(&versions, format!("# released\n{} 7\n", KEY)).expect("write versions");
+    let page = format!("{}/docs/page.md", tree.path());
+    let text = format!(
+        "Links\r\n- dat://{key}/a.md and hyper://{key}\r\n- dat://{key}+2/kept.md\r\n",
+        key = KEY
+    );
+    std::fs::write(&page, &text).expect("write page");
+    (versions, page, text)
+}
+
+#[test]
+fn it_shows_pins_as_a_diff_on_dry_runs() {
+    let tree = Tree::new("pin-dry-run");
+    let (versions, page, text) = write_pin_fixtures(&tree);
+
+    let output = run(&["pin", "--versions", &versions, "--dry-run", &page], "");
+
+    assert!(output.status.success());
+    assert_eq!(
+        format!(
+            "--- {page}\n\
+             +++ {page}\n\
+             @@ -2,1 +2,1 @@\n\
+             -- dat://{key}/a.md and hyper://{key}\n\
+             +- dat://{key}+7/a.md and hyper://{key}+7\n",
+            page = page,
+            key = KEY
+        ),
+        stdout(&output)
+    );
+    assert_eq!(text, std::fs::read_to_string(&page).expect("read page"));
+}
+
+#[test]
+fn it_pins_files_in_place() {
+    let tree = Tree::new("pin");
+    let (versions, page, text) = write_pin_fixtures(&tree);
+
+    let output = run(
+        &[
+            "pin",
+            "--versions",
+            &versions,
+            "--include",
+            "*.md",
+            tree.path(),
+        ],
+        "",
+    );
+
+    assert!(output.status.success());
+    assert_eq!(format!("{}: 2 links pinned\n", page), stdout(&output));
+    assert_eq!(
+        text.replace(&format!("{}/a.md", KEY), &format!("{}+7/a.md", KEY))
+            .replace(&format!("{}\r\n-", KEY), &format!("{}+7\r\n-", KEY)),
+        std::fs::read_to_string(&page).expect("read page")
+    );
+    assert!(std::fs::read_dir(format!("{}/docs", tree.path()))
+        .expect("read docs")
+        .all(|entry| !entry
+            .expect("read entry")
+            .file_name()
+            .to_string_lossy()
+            .ends_with(".tmp")));
+}
+
+#[test]
+fn it_marks_last_lines_without_a_newline_on_dry_runs() {
+    let tree = Tree::new("pin-no-newline");
+    let (versions, page, _) = write_pin_fixtures(&tree);
+    std::fs::write(&page, format!("See dat://{}", KEY)).expect("write page");
+
+    let output = run(&["pin", "--versions", &versions, "--dry-run", &page], "");
+
+    assert!(output.status.success());
+    assert_eq!(
+        format!(
+            "--- {page}\n\
+             +++ {page}\n\
+             @@ -1,1 +1,1 @@\n\
+             -See dat://{key}\n\
+             \\ No newline at end of file\n\
+             +See dat://{key}+7\n\
+             \\ No newline at end of file\n",
+            page = page,
+            key = KEY
+        ),
+        stdout(&output)
+    );
+}
+
+#[test]
+fn it_rejects_malformed_versions_files() {
+    let tree = Tree::new("pin-invalid");
+    let versions = format!("{}/versions.txt", tree.path());
+    std::fs::write(&versions, format!("{} 01x\n", KEY)).expect("write versions");
+
+    let output = run(&["pin", "--versions", &versions, tree.path()], "");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("line 1: invalid version"));
+
+    std::fs::write(&versions, format!("{} v1/x\n", KEY)).expect("write versions");
+    let output = run(&["pin", "--versions", &versions, tree.path()], "");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("line 1: invalid version"));
+}
@@ -0,0 +1,132 @@
+use parse_dat_url::resolve::{DnsResolver, ResolveError, Resolver, TxtRecord, MAX_TTL};
+use parse_dat_url::{DatKey, DatUrl};
+use pretty_assertions::assert_eq;
+use std::collections::HashMap;
+use std::time::Duration;
+
+const KEY: &str = "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21";
+const OTHER_KEY: &str = "b0c5c0ec5c7e7a5f5a4a3f6a8c1ee2b0b2e93b3e5e0a6c5d7b9c3c1c0e6b7a5d";
+
+fn record(text: &str, ttl: u64) -> TxtRecord {
+    TxtRecord {
+        text: text.to_string(),
+        ttl: Some(Duration::from_secs(ttl)),
+    }
+}
+
+/// In-memory stand-in for a DNS server, holding the TXT records of each name.
+fn zone() -> HashMap<&'static str, Vec<TxtRecord>> {
+    let mut zone = HashMap::new();
+    zone.insert(
+        "example.com",
+        vec![
+            record("v=spf1 -all", 300),
+            record(&format!("datkey={}", KEY), 3600),
+        ],
+    );
+    zone.insert(
+        "duplicated.example.com",
+        vec![
+            record(&format!("datkey={}", KEY), 600),
+            record(&format!("DATKEY={}", KEY.to_uppercase()), 60),
+        ],
+    );
+    zone.insert(
+        "conflicting.example.com",
+        vec![
+            record(&format!("datkey={}", KEY), 60),
+            record(&format!("datkey={}", OTHER_KEY), 60),
+        ],
+    );
+    zone.insert(
+        "malformed.example.com",
+        vec![
+            record(&format!("datkey={}", KEY), 60),
+            record("datkey=584faa05", 60),
+        ],
+    );
+    zone.insert("other.example.com", vec![record("v=spf1 -all", 300)]);
+    zone
+}
+
+fn resolver() -> DnsResolver<impl Fn(&str) -> Result<Vec<TxtRecord>, ResolveError>> {
+    let zone = zone();
+    DnsResolver::new(move |domain: &str| Ok(zone.get(domain).cloned().unwrap_or_default()))
+}
+
+#[test]
+fn it_resolves_datkey_records() -> Result<(), Box<dyn std::error::Error>> {
+    let resolution = resolver().lookup("example.com")?;
+    assert_eq!(KEY.parse::<DatKey>()?, resolution.key);
+    assert_eq!(Some(Duration::from_secs(3600)), resolution.ttl);
+
+    let dat_url = DatUrl::parse("dat://example.com+5/index.html")?;
+    assert_eq!(
+        format!("dat://{}+5/index.html", KEY),
+        resolver().resolve(&dat_url)?.to_string()
+    );
+    Ok(())
+}
+
+#[test]
+fn it_accepts_several_records_with_the_same_key() -> Result<(), Box<dyn std::error::Error>> {
+    let resolution = resolver().lookup("duplicated.example.com")?;
+    assert_eq!(KEY.parse::<DatKey>()?, resolution.key);
+    assert_eq!(Some(Duration::from_secs(60)), resolution.ttl);
+    Ok(())
+}
+
+#[test]
+fn it_clamps_long_ttls() -> Result<(), Box<dyn std::error::Error>> {
+    let resolver =
+        DnsResolver::new(|_: &str| Ok(vec![record(&format!("datkey={}", KEY), u64::MAX)]));
+    assert_eq!(Some(MAX_TTL), resolver.lookup("example.com")?.ttl);
+    Ok(())
+}
+
+#[test]
+fn it_rejects_conflicting_records() -> Result<(), Box<dyn std::error::Error>> {
+    assert_eq!(
+        Err(ResolveError::ConflictingRecords(vec![
+            KEY.parse()?,
+            OTHER_KEY.parse()?
+        ])),
+        resolver().lookup("conflicting.example.com")
+    );
+    Ok(())
+}
+
+#[test]
+fn it_rejects_malformed_records() {
+    assert_eq!(
+        Err(ResolveError::MalformedRecord),
+        resolver().lookup("malformed.example.com")
+    );
+}
+
+#[test]
+fn it_reports_missing_records() {
+    assert_eq!(
+        Err(ResolveError::MissingRecord),
+        resolver().lookup("other.example.com")
+    );
+    assert_eq!(
+        Err(ResolveError::MissingRecord),
+        resolver().lookup("unknown.example.com")
+    );
+}
+
+#[test]
+fn it_reports_transport_failures() {
+    let resolver = DnsResolver::new(|_: &str| -> Result<Vec<TxtRecord>, ResolveError> {
+        Err(ResolveError::Transport("timed out".to_string()))
+    });
+    assert_eq!(
+        Err(ResolveError::Transport("timed out".to_string())),
+        resolver.lookup("example.com")
+    );
+    assert_eq!(
+        Err(ResolveError::InvalidDomain),
+        resolver.lookup("192.0.2.0")
+    );
+}
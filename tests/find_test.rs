@@ -0,0 +1,98 @@
+use parse_dat_url::find_iter;
+use parse_dat_url::DatUrl;
+use parse_dat_url::Error as ParseError;
+use pretty_assertions::assert_eq;
+
+const KEY: &str = "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21";
+
+fn found(text: &str) -> Vec<&str> {
+    find_iter(text).map(|(range, _)| &text[range]).collect()
+}
+
+#[test]
+fn it_finds_urls_in_text() -> Result<(), ParseError> {
+    let text = format!(
+        "Get dat://{key}+5/index.html or hyper://{key}/ and dat://example.com, then reload.",
+        key = KEY
+    );
+    let matches: Vec<_> = find_iter(&text).collect();
+
+    assert_eq!(3, matches.len());
+    assert_eq!(
+        format!("dat://{}+5/index.html", KEY),
+        &text[matches[0].0.clone()]
+    );
+    assert_eq!(DatUrl::parse(&text[matches[0].0.clone()])?, matches[0].1);
+    assert!(matches[1].1.is_hyper());
+    assert_eq!("dat://example.com", &text[matches[2].0.clone()]);
+    Ok(())
+}
+
+#[test]
+fn it_finds_bare_keys() {
+    let text = format!("key: {key}, versioned {key}+3/a.txt.", key = KEY);
+    assert_eq!(
+        vec![KEY.to_string(), format!("{}+3/a.txt", KEY)],
+        found(&text)
+    );
+}
+
+#[test]
+fn it_skips_keys_inside_other_text() {
+    let text = format!(
+        "https://example.com/{key} x{key} {key}0 {short}",
+        key = KEY,
+        short = &KEY[1..]
+    );
+    assert!(found(&text).is_empty());
+}
+
+#[test]
+fn it_trims_trailing_punctuation() {
+    let text = "See dat://example.com/a.txt. Or dat://example.com/b?, dat://example.com/c!!";
+    assert_eq!(
+        vec![
+            "dat://example.com/a.txt",
+            "dat://example.com/b",
+            "dat://example.com/c"
+        ],
+        found(text)
+    );
+}
+
+#[test]
+fn it_balances_parentheses() {
+    let text = "(see dat://example.com/a) and dat://example.com/wiki/Foo_(bar).";
+    assert_eq!(
+        vec!["dat://example.com/a", "dat://example.com/wiki/Foo_(bar)"],
+        found(text)
+    );
+}
+
+#[test]
+fn it_handles_markdown_links() {
+    let text = "[dat://example.com/](dat://example.com/docs) <hyper://example.com/>";
+    assert_eq!(
+        vec![
+            "dat://example.com/",
+            "dat://example.com/docs",
+            "hyper://example.com/"
+        ],
+        found(text)
+    );
+}
+
+#[test]
+fn it_reports_byte_ranges_after_multibyte_text() {
+    let text = "é → dat://example.com/ ✓";
+    let matches: Vec<_> = find_iter(text).collect();
+    assert_eq!(1, matches.len());
+    assert_eq!(7..25, matches[0].0);
+}
+
+#[test]
+fn it_ignores_other_schemes_and_invalid_urls() {
+    let text =
+        "https://example.com/ dat:// xdat://example.com dat://+5 hyper://example.com+preview/";
+    assert_eq!(Vec::<&str>::new(), found(text));
+}
@@ -0,0 +1,215 @@
+use parse_dat_url::{DatUrl, Error as ParseError, GatewayConfig, GatewayLayout};
+use pretty_assertions::assert_eq;
+use url::Url;
+
+const KEY: &str = "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21";
+const BASE32_KEY: &str = "lbh2ubotsqmqvmnd6asamb7zx4vx4k6zs2edbii46563btvdniqq";
+
+fn round_trip(config: &GatewayConfig, url: &str, gateway_url: &str) -> Result<(), ParseError> {
+    let dat_url = DatUrl::parse(url)?;
+    let mapped = dat_url.to_gateway_url(config);
+    assert_eq!(gateway_url, mapped.as_str());
+
+    let back = DatUrl::from_gateway_url(&mapped, config)?;
+    assert_eq!(dat_url, back);
+    assert_eq!(dat_url.version(), back.version());
+    assert_eq!(dat_url.path(), back.path());
+    Ok(())
+}
+
+#[test]
+fn it_maps_urls_on_the_path_layout() -> Result<(), ParseError> {
+    let config = GatewayConfig::path("http://gateway")?;
+    assert_eq!(GatewayLayout::Path, config.layout());
+    assert_eq!("http://gateway/", config.base().as_str());
+
+    let cases = &[
+        (
+            format!("dat://{}+5/index.html", KEY),
+            format!("http://gateway/{}+5/index.html", KEY),
+        ),
+        (format!("dat://{}", KEY), format!("http://gateway/{}", KEY)),
+        (
+            format!("dat://{}/", KEY),
+            format!("http://gateway/{}/", KEY),
+        ),
+        (
+            format!("dat://{}+5", KEY),
+            format!("http://gateway/{}+5", KEY),
+        ),
+        (
+            format!("dat://{}+0.0.0.1/path/to+file.txt?a=1#top", KEY),
+            format!("http://gateway/{}+0.0.0.1/path/to+file.txt?a=1#top", KEY),
+        ),
+        (
+            format!("dat://{}/+5/read%20me.md", KEY),
+            format!("http://gateway/{}/+5/read%20me.md", KEY),
+        ),
+        (
+            "dat://example.com+latest/docs/".into(),
+            "http://gateway/example.com+latest/docs/".into(),
+        ),
+        (
+            "dat://example.com:3282/".into(),
+            "http://gateway/example.com:3282/".into(),
+        ),
+    ];
+    for (url, gateway_url) in cases {
+        round_trip(&config, url, gateway_url)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn it_maps_urls_under_a_path_prefix() -> Result<(), ParseError> {
+    let config = GatewayConfig::path("https://example.org/dat?ignored#ignored")?;
+    assert_eq!("https://example.org/dat/", config.base().as_str());
+
+    round_trip(
+        &config,
+        &format!("dat://{}+c2/index.html", KEY),
+        &format!("https://example.org/dat/{}+c2/index.html", KEY),
+    )
+}
+
+#[test]
+fn it_maps_urls_on_the_subdomain_layout() -> Result<(), ParseError> {
+    let config = GatewayConfig::subdomain("https://gateway.tld/")?;
+    assert_eq!(GatewayLayout::Subdomain, config.layout());
+
+    let cases = &[
+        (
+            format!("dat://{}+5/index.html", KEY),
+            format!("https://{}.gateway.tld/+5/index.html", BASE32_KEY),
+        ),
+        (
+            format!("dat://{}/index.html", KEY),
+            format!("https://{}.gateway.tld/index.html", BASE32_KEY),
+        ),
+        (
+            format!("dat://{}/", KEY),
+            format!("https://{}.gateway.tld/", BASE32_KEY),
+        ),
+        (
+            format!("dat://{}", KEY),
+            format!("https://{}.gateway.tld/+", BASE32_KEY),
+        ),
+        (
+            format!("dat://{}+5", KEY),
+            format!("https://{}.gateway.tld/+5", BASE32_KEY),
+        ),
+        (
+            format!("dat://{}+5/", KEY),
+            format!("https://{}.gateway.tld/+5/", BASE32_KEY),
+        ),
+        (
+            format!("dat://{}/+5/file.txt", KEY),
+            format!("https://{}.gateway.tld/+/+5/file.txt", BASE32_KEY),
+        ),
+        (
+            format!("dat://{}+v1.0/path/to+file.txt?a=1#top", KEY),
+            format!(
+                "https://{}.gateway.tld/+v1.0/path/to+file.txt?a=1#top",
+                BASE32_KEY
+            ),
+        ),
+    ];
+    for (url, gateway_url) in cases {
+        round_trip(&config, url, gateway_url)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn it_falls_back_to_the_path_layout_without_a_key() -> Result<(), ParseError> {
+    let config = GatewayConfig::subdomain("https://gateway.tld")?;
+
+    round_trip(
+        &config,
+        "dat://example.com+5/index.html",
+        "https://gateway.tld/example.com+5/index.html",
+    )?;
+    round_trip(
+        &config,
+        &format!("dat://{}:3282/", KEY),
+        &format!("https://gateway.tld/{}:3282/", KEY),
+    )
+}
+
+#[test]
+fn it_uses_the_scheme_of_the_configuration() -> Result<(), ParseError> {
+    let config = GatewayConfig::subdomain("https://gateway.tld")?.scheme("hyper://")?;
+    let url = Url::parse(&format!("https://{}.gateway.tld/+5/", BASE32_KEY)).unwrap();
+
+    assert_eq!(
+        format!("hyper://{}+5/", KEY),
+        DatUrl::from_gateway_url(&url, &config)?.to_string()
+    );
+    assert_eq!(
+        Err(ParseError::UnsupportedScheme),
+        GatewayConfig::path("http://gateway").and_then(|config| config.scheme("https"))
+    );
+    Ok(())
+}
+
+#[test]
+fn it_rejects_invalid_configurations() {
+    assert!(matches!(
+        GatewayConfig::path("not a url"),
+        Err(ParseError::InvalidUrl(_))
+    ));
+    assert_eq!(
+        Err(ParseError::MissingHostname),
+        GatewayConfig::path("mailto:gateway@example.com")
+    );
+    assert_eq!(
+        Err(ParseError::MissingHostname),
+        GatewayConfig::subdomain("http://127.0.0.1:8080/")
+    );
+    assert!(GatewayConfig::path("http://127.0.0.1:8080/").is_ok());
+}
+
+#[test]
+fn it_rejects_urls_not_served_by_the_gateway() -> Result<(), ParseError> {
+    let path = GatewayConfig::path("http://gateway/dat/")?;
+    let subdomain = GatewayConfig::subdomain("https://gateway.tld")?;
+
+    let cases = &[
+        (&path, format!("https://gateway/dat/{}/", KEY)),
+        (&path, format!("http://gateway:8080/dat/{}/", KEY)),
+        (&path, format!("http://other/dat/{}/", KEY)),
+        (&path, format!("http://gateway/{}/", KEY)),
+        (&path, format!("http://{}.gateway/dat/", BASE32_KEY)),
+        (&subdomain, format!("https://{}.example.org/", BASE32_KEY)),
+        (
+            &subdomain,
+            format!("https://{}.notgateway.tld/", BASE32_KEY),
+        ),
+    ];
+    for (config, url) in cases {
+        let url = Url::parse(url).unwrap();
+        assert_eq!(
+            Err(ParseError::NotOnGateway),
+            DatUrl::from_gateway_url(&url, config),
+            "{}",
+            url
+        );
+    }
+
+    let url = Url::parse("http://gateway/dat/").unwrap();
+    assert_eq!(
+        Err(ParseError::MissingHostname),
+        DatUrl::from_gateway_url(&url, &path)
+    );
+    let url = Url::parse("https://notakey.gateway.tld/").unwrap();
+    assert_eq!(
+        Err(ParseError::InvalidKey),
+        DatUrl::from_gateway_url(&url, &subdomain)
+    );
+    let url = Url::parse(&format!("http://gateway/dat/{}+01x/", KEY)).unwrap();
+    assert_eq!(
+        Err(ParseError::InvalidVersion),
+        DatUrl::from_gateway_url(&url, &path)
+    );
+    Ok(())
+}
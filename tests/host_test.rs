@@ -0,0 +1,62 @@
+use parse_dat_url::DatUrl;
+use parse_dat_url::Error as ParseError;
+use parse_dat_url::{DatHost, DatKey};
+use pretty_assertions::assert_eq;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+const KEY: &str = "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21";
+
+#[test]
+fn it_classifies_keys() -> Result<(), ParseError> {
+    let key: DatKey = KEY.parse()?;
+
+    assert_eq!(DatHost::Key(key, None), DatUrl::parse(KEY)?.dat_host());
+    assert_eq!(
+        DatHost::Key(key, Some(3282)),
+        DatUrl::parse(&format!("dat://{}:3282+1/file.txt", KEY))?.dat_host()
+    );
+    Ok(())
+}
+
+#[test]
+fn it_classifies_domains() -> Result<(), ParseError> {
+    assert_eq!(
+        DatHost::Domain("example.com", None),
+        DatUrl::parse("dat://example.com+v1.0.0/file.txt")?.dat_host()
+    );
+    assert_eq!(
+        DatHost::Domain("example.com", Some(8080)),
+        DatUrl::parse("example.com:8080")?.dat_host()
+    );
+    Ok(())
+}
+
+#[test]
+fn it_classifies_ip_addresses() -> Result<(), ParseError> {
+    assert_eq!(
+        DatHost::Ipv4(Ipv4Addr::new(192, 0, 2, 0), None),
+        DatUrl::parse("192.0.2.0+0.0.0.1/path/to+file.txt")?.dat_host()
+    );
+    assert_eq!(
+        DatHost::Ipv4(Ipv4Addr::new(192, 0, 2, 0), None),
+        DatUrl::parse("http://192.0.2.0/")?.dat_host()
+    );
+    assert_eq!(
+        DatHost::Ipv6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0), None),
+        DatUrl::parse("[2001:DB8::0]+0.0.0.1/path/to+file.txt")?.dat_host()
+    );
+    Ok(())
+}
+
+#[test]
+fn it_displays_the_host_with_port() -> Result<(), ParseError> {
+    assert_eq!(
+        "[2001:db8::]:3282",
+        DatUrl::parse("[2001:DB8::0]:3282")?.dat_host().to_string()
+    );
+    assert_eq!(
+        "example.com",
+        DatUrl::parse("example.com/")?.dat_host().to_string()
+    );
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use parse_dat_url::resolve::{HostsResolver, HOSTS_ENV};
+use pretty_assertions::assert_eq;
+use std::env;
+
+const KEY: &str = "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21";
+
+// Kept in a binary of its own, since changing the environment affects every test running in the same process.
+#[test]
+fn it_reads_the_file_named_by_the_environment() -> Result<(), Box<dyn std::error::Error>> {
+    let path = env::temp_dir().join(format!("parse-dat-url-hosts-env-{}", std::process::id()));
+    let hosts_file = format!("{} staging.example.com\n", KEY);
+    std::fs::write(&path, &hosts_file)?;
+
+    let previous = env::var_os(HOSTS_ENV);
+    env::set_var(HOSTS_ENV, &path);
+    let hosts = HostsResolver::from_env();
+    env::remove_var(HOSTS_ENV);
+    let unset = HostsResolver::from_env();
+    if let Some(previous) = previous {
+        env::set_var(HOSTS_ENV, previous);
+    }
+    std::fs::remove_file(&path)?;
+
+    assert_eq!(HostsResolver::parse(&hosts_file)?, hosts?);
+    assert!(unset?.is_empty());
+    Ok(())
+}
@@ -0,0 +1,102 @@
+use parse_dat_url::resolve::{
+    HostsResolver, Resolution, ResolveError, Resolver, WellKnownResolver,
+};
+use parse_dat_url::{DatKey, DatUrl};
+use pretty_assertions::assert_eq;
+use std::io;
+use std::time::Duration;
+use url::Url;
+
+const KEY: &str = "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21";
+const OTHER_KEY: &str = "b0c5c0ec5c7e7a5f5a4a3f6a8c1ee2b0b2e93b3e5e0a6c5d7b9c3c1c0e6b7a5d";
+
+fn hosts_file() -> String {
+    format!(
+        "# Local archives\n\
+         {key}  staging.example.com   WWW.Staging.example.com # inline comment\n\
+         \n\
+         dat://{other}/  dev.example.com\n",
+        key = KEY,
+        other = OTHER_KEY
+    )
+}
+
+#[test]
+fn it_parses_hosts_files() -> Result<(), Box<dyn std::error::Error>> {
+    let hosts = HostsResolver::parse(&hosts_file())?;
+    assert_eq!(3, hosts.len());
+
+    let key: DatKey = KEY.parse()?;
+    assert_eq!(
+        Ok(Resolution {
+            key,
+            ttl: Some(Duration::ZERO)
+        }),
+        hosts.lookup("www.staging.example.com")
+    );
+    assert_eq!(OTHER_KEY, hosts.lookup("DEV.example.com")?.key.to_hex());
+    assert_eq!(
+        Err(ResolveError::MissingRecord),
+        hosts.lookup("example.com")
+    );
+    Ok(())
+}
+
+#[test]
+fn it_rejects_malformed_hosts_files() {
+    let files = [
+        "584faa05 staging.example.com".to_string(),
+        KEY.to_string(),
+        format!("dat://{}+5 staging.example.com", KEY),
+        format!(
+            "{} staging.example.com\n{} staging.example.com",
+            KEY, OTHER_KEY
+        ),
+    ];
+    for file in &files {
+        let error = HostsResolver::parse(file).unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidData, error.kind(), "{}", file);
+    }
+    assert_eq!(
+        "line 2: domain mapped to several keys",
+        HostsResolver::parse(&files[3]).unwrap_err().to_string()
+    );
+}
+
+#[test]
+fn it_overrides_network_backends() -> Result<(), Box<dyn std::error::Error>> {
+    let network = WellKnownResolver::new(|url: &Url| {
+        assert_eq!(Some("example.com"), url.host_str());
+        Ok(parse_dat_url::resolve::HttpResponse {
+            status: 200,
+            body: format!("dat://{}", OTHER_KEY),
+        })
+    });
+    let mut hosts = HostsResolver::new();
+    hosts.insert("Staging.example.com", KEY.parse()?);
+    let resolver = hosts.or(network);
+
+    let dat_url = DatUrl::parse("dat://staging.example.com+5/index.html")?;
+    assert_eq!(
+        format!("dat://{}+5/index.html", KEY),
+        resolver.resolve(&dat_url)?.to_string()
+    );
+    assert_eq!(OTHER_KEY, resolver.lookup("example.com")?.key.to_hex());
+    Ok(())
+}
+
+#[test]
+fn it_reads_hosts_files() -> Result<(), Box<dyn std::error::Error>> {
+    let path = std::env::temp_dir().join(format!("parse-dat-url-hosts-{}", std::process::id()));
+    std::fs::write(&path, hosts_file())?;
+
+    let hosts = HostsResolver::from_file(&path);
+    std::fs::remove_file(&path)?;
+
+    assert_eq!(HostsResolver::parse(&hosts_file())?, hosts?);
+    assert_eq!(
+        io::ErrorKind::NotFound,
+        HostsResolver::from_file(&path).unwrap_err().kind()
+    );
+    Ok(())
+}
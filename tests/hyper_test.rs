@@ -0,0 +1,57 @@
+use parse_dat_url::DatUrl;
+use parse_dat_url::Error as ParseError;
+use parse_dat_url::Version;
+use pretty_assertions::assert_eq;
+
+const KEY: &str = "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21";
+
+#[test]
+fn it_recognises_the_hyper_scheme() -> Result<(), ParseError> {
+    let hyper: DatUrl = format!("hyper://{}+5/file.txt", KEY).parse()?;
+    assert!(hyper.is_hyper());
+    assert!(!hyper.is_dat());
+    assert_eq!("hyper://", hyper.scheme());
+    assert_eq!(&Some(Version::Number(5)), hyper.version());
+
+    let dat: DatUrl = format!("{}+5/file.txt", KEY).parse()?;
+    assert!(dat.is_dat());
+    assert!(!dat.is_hyper());
+
+    assert!(DatUrl::parse(&format!("HYPER://{}/", KEY))?.is_hyper());
+    assert!(!DatUrl::parse("https://example.com/")?.is_hyper());
+    Ok(())
+}
+
+#[test]
+fn it_applies_hyper_versioning_rules() -> Result<(), ParseError> {
+    for version in &["1", "latest"] {
+        DatUrl::parse(&format!("hyper://{}+{}/", KEY, version))?;
+    }
+    for version in &["c1", "v1.0.0", "0.0.0.1", "preview"] {
+        assert_eq!(
+            Err(ParseError::InvalidVersion),
+            DatUrl::parse(&format!("hyper://{}+{}/", KEY, version))
+        );
+        DatUrl::parse(&format!("dat://{}+{}/", KEY, version))?;
+    }
+    Ok(())
+}
+
+#[test]
+fn it_converts_between_dat_and_hyper() -> Result<(), ParseError> {
+    let dat: DatUrl = format!("dat://{}+5/file.txt?x=1#top", KEY).parse()?;
+    let hyper: DatUrl = format!("hyper://{}+5/file.txt?x=1#top", KEY).parse()?;
+
+    assert_eq!(hyper, dat.to_hyper()?);
+    assert_eq!(dat, hyper.to_dat()?);
+    assert_eq!(dat.key(), hyper.key());
+    assert_eq!(hyper, hyper.to_hyper()?);
+    Ok(())
+}
+
+#[test]
+fn it_refuses_to_convert_incompatible_versions_to_hyper() -> Result<(), ParseError> {
+    let dat: DatUrl = format!("dat://{}+c1/file.txt", KEY).parse()?;
+    assert_eq!(Err(ParseError::InvalidVersion), dat.to_hyper());
+    Ok(())
+}
@@ -0,0 +1,133 @@
+use parse_dat_url::DatUrl;
+use parse_dat_url::Error as ParseError;
+use pretty_assertions::assert_eq;
+
+const KEY: &str = "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21";
+
+#[test]
+fn it_resolves_rfc3986_normal_examples() -> Result<(), ParseError> {
+    // https://tools.ietf.org/html/rfc3986#section-5.4.1, with `http://a` replaced by a versioned dat url.
+    let base_str = format!("dat://{}+5/b/c/d;p?q", KEY);
+    let base = DatUrl::parse(&base_str)?;
+    let examples = &[
+        ("g", "/b/c/g"),
+        ("./g", "/b/c/g"),
+        ("g/", "/b/c/g/"),
+        ("/g", "/g"),
+        ("?y", "/b/c/d;p?y"),
+        ("g?y", "/b/c/g?y"),
+        ("#s", "/b/c/d;p?q#s"),
+        ("g#s", "/b/c/g#s"),
+        ("g?y#s", "/b/c/g?y#s"),
+        (";x", "/b/c/;x"),
+        ("g;x", "/b/c/g;x"),
+        ("g;x?y#s", "/b/c/g;x?y#s"),
+        ("", "/b/c/d;p?q"),
+        (".", "/b/c/"),
+        ("./", "/b/c/"),
+        ("..", "/b/"),
+        ("../", "/b/"),
+        ("../g", "/b/g"),
+        ("../..", "/"),
+        ("../../", "/"),
+        ("../../g", "/g"),
+    ];
+
+    for (reference, expected) in examples {
+        let expected = format!("dat://{}+5{}", KEY, expected);
+        let joined = base.join(reference)?;
+        assert_eq!(expected, joined.to_string(), "joining {:?}", reference);
+        assert_eq!(DatUrl::parse(&expected)?, joined, "joining {:?}", reference);
+    }
+    Ok(())
+}
+
+#[test]
+fn it_resolves_rfc3986_abnormal_examples() -> Result<(), ParseError> {
+    // https://tools.ietf.org/html/rfc3986#section-5.4.2
+    let base_str = format!("dat://{}+5/b/c/d;p?q", KEY);
+    let base = DatUrl::parse(&base_str)?;
+    let examples = &[
+        ("../../../g", "/g"),
+        ("../../../../g", "/g"),
+        ("/./g", "/g"),
+        ("/../g", "/g"),
+        ("g.", "/b/c/g."),
+        (".g", "/b/c/.g"),
+        ("g..", "/b/c/g.."),
+        ("..g", "/b/c/..g"),
+        ("./../g", "/b/g"),
+        ("./g/.", "/b/c/g/"),
+        ("g/./h", "/b/c/g/h"),
+        ("g/../h", "/b/c/h"),
+    ];
+
+    for (reference, expected) in examples {
+        let expected = format!("dat://{}+5{}", KEY, expected);
+        assert_eq!(
+            expected,
+            base.join(reference)?.to_string(),
+            "joining {:?}",
+            reference
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn it_keeps_the_base_without_path() -> Result<(), ParseError> {
+    let base = DatUrl::parse("example.com+v1.0.0")?;
+
+    assert_eq!(
+        DatUrl::parse("dat://example.com+v1.0.0/index.html")?,
+        base.join("index.html")?
+    );
+    assert_eq!(
+        DatUrl::parse("dat://example.com+v1.0.0/index.html")?,
+        base.join("/index.html")?
+    );
+    Ok(())
+}
+
+#[test]
+fn it_replaces_the_base_with_absolute_references() -> Result<(), ParseError> {
+    let base_str = format!("hyper://{}+5/blog/post.html", KEY);
+    let base = DatUrl::parse(&base_str)?;
+
+    assert_eq!(
+        DatUrl::parse("dat://example.com+2/file.txt")?,
+        base.join("dat://example.com+2/file.txt")?
+    );
+    assert_eq!(
+        DatUrl::parse("hyper://example.com/file.txt")?,
+        base.join("//example.com/file.txt")?
+    );
+    assert_eq!(
+        DatUrl::parse("DAT://example.com/")?,
+        base.join("DAT://example.com/")?
+    );
+    Ok(())
+}
+
+#[test]
+fn it_rejects_references_with_other_schemes() -> Result<(), ParseError> {
+    let base_str = format!("dat://{}+5/blog/post.html", KEY);
+    let base = DatUrl::parse(&base_str)?;
+
+    // https://tools.ietf.org/html/rfc3986#section-5.4.1 resolves `g:h` to itself, which is not a dat url.
+    for reference in &[
+        "https://example.com/",
+        "http://x",
+        "mailto:a@b",
+        "g:h",
+        "file:///etc/hosts",
+    ] {
+        assert_eq!(
+            Err(ParseError::UnsupportedScheme),
+            base.join(reference),
+            "joining {:?}",
+            reference
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,153 @@
+use parse_dat_url::DatKey;
+use parse_dat_url::DatUrl;
+use parse_dat_url::Error as ParseError;
+use pretty_assertions::assert_eq;
+use std::collections::HashSet;
+
+const KEY: &str = "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21";
+
+#[test]
+fn it_round_trips_hex() -> Result<(), ParseError> {
+    let key: DatKey = KEY.parse()?;
+    assert_eq!(KEY, key.to_string());
+    assert_eq!(0x58, key.as_bytes()[0]);
+    assert_eq!(0x21, key.as_bytes()[31]);
+    assert_eq!(key, DatKey::from_bytes(*key.as_bytes()));
+    Ok(())
+}
+
+#[test]
+fn it_accepts_uppercase_hex() -> Result<(), ParseError> {
+    assert_eq!(
+        DatKey::from_hex(KEY)?,
+        DatKey::from_hex(&KEY.to_uppercase())?
+    );
+    Ok(())
+}
+
+#[test]
+fn it_rejects_invalid_keys() {
+    assert_eq!(Err(ParseError::InvalidKey), DatKey::from_hex(""));
+    assert_eq!(Err(ParseError::InvalidKey), DatKey::from_hex(&KEY[1..]));
+    assert_eq!(
+        Err(ParseError::InvalidKey),
+        DatKey::from_hex(&format!("{}00", KEY))
+    );
+    assert_eq!(
+        Err(ParseError::InvalidKey),
+        DatKey::from_hex(&format!("{}zz", &KEY[2..]))
+    );
+    assert_eq!(Err(ParseError::InvalidKey), "example.com".parse::<DatKey>());
+}
+
+#[test]
+fn it_compares_and_hashes_keys() -> Result<(), ParseError> {
+    let low = DatKey::from_bytes([0; 32]);
+    let high = DatKey::from_bytes([0xff; 32]);
+    let key: DatKey = KEY.parse()?;
+
+    assert!(low < key && key < high);
+    assert_ne!(low, high);
+
+    let set: HashSet<DatKey> = vec![key, KEY.parse()?, low].into_iter().collect();
+    assert_eq!(2, set.len());
+    Ok(())
+}
+
+#[test]
+fn it_extracts_the_key_from_the_url() -> Result<(), ParseError> {
+    let key: DatKey = KEY.parse()?;
+
+    assert_eq!(Some(key), DatUrl::parse(KEY)?.key());
+    assert_eq!(
+        Some(key),
+        DatUrl::parse(&format!("dat://{}+1/file.txt", KEY))?.key()
+    );
+    assert_eq!(
+        Some(key),
+        DatUrl::parse(&format!("dat://{}:3282/file.txt", KEY))?.key()
+    );
+    assert_eq!(None, DatUrl::parse("dat://example.com+1/file.txt")?.key());
+    assert_eq!(None, DatUrl::parse("dat://192.0.2.0/")?.key());
+    Ok(())
+}
+
+#[test]
+fn it_derives_discovery_keys() -> Result<(), ParseError> {
+    // hypercore-crypto derives the discovery key as `crypto_generichash(out, Buffer.from('hypercore'), publicKey)`,
+    // libsodium's BLAKE2b with a 32 bytes output, keyed by the public key:
+    // https://github.com/mafintosh/hypercore-crypto/blob/master/index.js
+    //
+    // The expected values come from CPython's BLAKE2 reference implementation, outside of this crate:
+    // `hashlib.blake2b(b"hypercore", key=bytes.fromhex(public_key), digest_size=32).hexdigest()`
+    let vectors = &[
+        (
+            "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21",
+            "7c9bac69b85656ee699f95924f9a6b9101b49ea9d145ea47edb64fb1a8eac874",
+        ),
+        (
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "6b7ecf9ce456d56e5145ec4c5661e0c79ee47ddcc9c0504e90aa6fd60746365c",
+        ),
+    ];
+
+    for (key, discovery_key) in vectors {
+        let key: DatKey = key.parse()?;
+        assert_eq!(*discovery_key, key.discovery_key().to_string());
+    }
+    Ok(())
+}
+
+#[test]
+fn it_derives_discovery_keys_from_the_url() -> Result<(), ParseError> {
+    let key: DatKey = KEY.parse()?;
+
+    assert_eq!(
+        Some(key.discovery_key()),
+        DatUrl::parse(&format!("dat://{}+c1/file.txt", KEY))?.discovery_key()
+    );
+    assert_eq!(
+        Some(key.discovery_key()),
+        DatUrl::parse(&format!("dat://{}:3282/", KEY))?.discovery_key()
+    );
+    assert_eq!(None, DatUrl::parse("dat://example.com/")?.discovery_key());
+    Ok(())
+}
+
+#[test]
+fn it_encodes_keys_as_base32() -> Result<(), ParseError> {
+    let vectors = &[
+        (KEY, "lbh2ubotsqmqvmnd6asamb7zx4vx4k6zs2edbii46563btvdniqq"),
+        (
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+        ),
+        (
+            "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+            "777777777777777777777777777777777777777777777777777q",
+        ),
+    ];
+
+    for (key, base32) in vectors {
+        let key: DatKey = key.parse()?;
+        assert_eq!(*base32, key.to_base32());
+        assert_eq!(key, DatKey::from_base32(base32)?);
+        assert_eq!(key, DatKey::from_base32(&base32.to_uppercase())?);
+    }
+    Ok(())
+}
+
+#[test]
+fn it_rejects_invalid_base32_keys() {
+    let invalid = &[
+        "",
+        "lbh2ubotsqmqvmnd6asamb7zx4vx4k6zs2edbii46563btvdniq",
+        "lbh2ubotsqmqvmnd6asamb7zx4vx4k6zs2edbii46563btvdniqqa",
+        "lbh2ubotsqmqvmnd6asamb7zx4vx4k6zs2edbii46563btvdniq1",
+        "lbh2ubotsqmqvmnd6asamb7zx4vx4k6zs2edbii46563btvdniqr",
+    ];
+
+    for base32 in invalid {
+        assert_eq!(Err(ParseError::InvalidKey), DatKey::from_base32(base32));
+    }
+}
@@ -0,0 +1,85 @@
+use parse_dat_url::DatUrl;
+use parse_dat_url::Error as ParseError;
+use pretty_assertions::assert_eq;
+
+const KEY: &str = "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21";
+
+#[test]
+fn default_options_behave_like_parse() {
+    for url in &[
+        KEY,
+        "example.com+1/file.txt",
+        "https://example.com/",
+        "dat://[",
+    ] {
+        assert_eq!(DatUrl::parse(url), DatUrl::options().parse(url));
+    }
+}
+
+#[test]
+fn it_uses_the_default_scheme() -> Result<(), ParseError> {
+    let dat_url = DatUrl::options().default_scheme("hyper").parse(KEY)?;
+    assert_eq!("hyper://", dat_url.scheme());
+    assert!(dat_url.is_hyper());
+
+    let dat_url = DatUrl::options().default_scheme("hyper://").parse(KEY)?;
+    assert_eq!(format!("hyper://{}", KEY), dat_url.to_string());
+
+    let url = format!("dat://{}", KEY);
+    let dat_url = DatUrl::options().default_scheme("hyper").parse(&url)?;
+    assert!(dat_url.is_dat());
+    Ok(())
+}
+
+#[test]
+fn it_restricts_the_allowed_schemes() -> Result<(), ParseError> {
+    let options = DatUrl::options().allowed_schemes(&["dat", "hyper"]);
+
+    options.parse(KEY)?;
+    options.parse("dat://example.com/")?;
+    options.parse("HYPER://example.com/")?;
+    for url in &[
+        "https://example.com/",
+        "ftp://example.com/",
+        "http://192.0.2.0/",
+    ] {
+        assert_eq!(Err(ParseError::UnsupportedScheme), options.parse(url));
+    }
+
+    let options = DatUrl::options().allowed_schemes(&["hyper"]);
+    assert_eq!(Err(ParseError::UnsupportedScheme), options.parse(KEY));
+    Ok(())
+}
+
+#[test]
+fn it_requires_key_hosts() -> Result<(), ParseError> {
+    let options = DatUrl::options().require_key(true);
+
+    options.parse(&format!("dat://{}+1/file.txt", KEY))?;
+    let url = format!("dat://{}:3282/", KEY);
+    let with_port = options.parse(&url)?;
+    assert_eq!(Some(KEY.parse()?), with_port.key());
+    for url in &[
+        "dat://example.com/",
+        "192.0.2.0",
+        "[2001:DB8::0]",
+        "example.com:3282",
+    ] {
+        assert_eq!(Err(ParseError::InvalidKey), options.parse(url));
+    }
+    Ok(())
+}
+
+#[test]
+fn it_forbids_versions() -> Result<(), ParseError> {
+    let options = DatUrl::options().forbid_version(true);
+
+    options.parse(&format!("dat://{}/file.txt", KEY))?;
+    for version in &["1", "c1", "v1.0.0", "latest"] {
+        assert_eq!(
+            Err(ParseError::UnexpectedVersion),
+            options.parse(&format!("dat://{}+{}/file.txt", KEY, version))
+        );
+    }
+    Ok(())
+}
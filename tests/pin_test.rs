@@ -0,0 +1,97 @@
+use parse_dat_url::{apply_pins, pin_versions, DatKey, Pin, Version};
+use pretty_assertions::assert_eq;
+
+const KEY: &str = "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21";
+const OTHER_KEY: &str = "b0c5c0ec5c7e7a5f5a4a3f6a8c1ee2b0b2e93b3e5e0a6c5d7b9c3c1c0e6b7a5d";
+
+fn version_of(key: &DatKey) -> Option<Version<'static>> {
+    if key.to_hex() == KEY {
+        Some(Version::Number(5))
+    } else {
+        None
+    }
+}
+
+#[test]
+fn it_pins_unversioned_links() {
+    let text = format!("See dat://{}/index.html.", KEY);
+    let pins = pin_versions(&text, version_of);
+
+    assert_eq!(
+        vec![Pin {
+            range: 4..85,
+            original: &text[4..85],
+            pinned: format!("dat://{}+5/index.html", KEY),
+        }],
+        pins
+    );
+    assert_eq!(
+        format!("See dat://{}+5/index.html.", KEY),
+        apply_pins(&text, &pins)
+    );
+}
+
+#[test]
+fn it_leaves_every_other_byte_untouched() {
+    let text = format!(
+        "# Links ✓\r\n\
+         - DAT://{key}/Read%20Me.md?x=1#top\r\n\
+         - [mirror](hyper://{key})\r\n\
+         - versioned dat://{key}+2/ and latest dat://{key}+latest/\r\n\
+         - unknown dat://{other}/ domain dat://example.com/ bare {key}\r\n",
+        key = KEY,
+        other = OTHER_KEY
+    );
+    let pins = pin_versions(&text, version_of);
+
+    assert_eq!(2, pins.len());
+    assert_eq!(
+        text.replacen(&format!("DAT://{}/", KEY), &format!("DAT://{}+5/", KEY), 1)
+            .replacen(
+                &format!("hyper://{})", KEY),
+                &format!("hyper://{}+5)", KEY),
+                1
+            ),
+        apply_pins(&text, &pins)
+    );
+}
+
+#[test]
+fn it_pins_key_hosts_with_a_port() {
+    let text = format!("dat://{}:3282/index.html", KEY);
+    let pins = pin_versions(&text, version_of);
+
+    assert_eq!(
+        format!("dat://{}:3282+5/index.html", KEY),
+        apply_pins(&text, &pins)
+    );
+}
+
+#[test]
+fn it_skips_versions_not_supported_by_hyper() {
+    let text = format!("dat://{key}/ hyper://{key}/", key = KEY);
+    let pins = pin_versions(&text, |_| Some(Version::Tag("v1.0".into())));
+
+    assert_eq!(1, pins.len());
+    assert_eq!(
+        format!("dat://{key}+v1.0/ hyper://{key}/", key = KEY),
+        apply_pins(&text, &pins)
+    );
+}
+
+#[test]
+fn it_skips_versions_which_do_not_round_trip() {
+    let text = format!("dat://{}/Guide.md", KEY);
+    for tag in &["v1/x", "v1?x", "v1#x", "v1+x", "latest", "1x"] {
+        let pins = pin_versions(&text, |_| Some(Version::Tag((*tag).into())));
+        assert!(pins.is_empty(), "{}", tag);
+    }
+}
+
+#[test]
+fn it_keeps_texts_without_pins() {
+    let text = "No links, only dat://example.com/.";
+    let pins = pin_versions(text, version_of);
+    assert!(pins.is_empty());
+    assert_eq!(text, apply_pins(text, &pins));
+}
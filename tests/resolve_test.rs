@@ -0,0 +1,210 @@
+use parse_dat_url::resolve::{
+    HttpResponse, Resolution, ResolveError, Resolver, WellKnownResolver, MAX_TTL,
+};
+use parse_dat_url::{DatKey, DatUrl};
+use pretty_assertions::assert_eq;
+use std::cell::RefCell;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::thread;
+use std::time::Duration;
+use url::Url;
+
+const KEY: &str = "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21";
+
+fn ok(body: &str) -> impl Fn(&Url) -> Result<HttpResponse, ResolveError> + '_ {
+    move |_: &Url| {
+        Ok(HttpResponse {
+            status: 200,
+            body: body.to_string(),
+        })
+    }
+}
+
+#[test]
+fn it_requests_the_well_known_file() -> Result<(), Box<dyn std::error::Error>> {
+    let requested = RefCell::new(Vec::new());
+    let resolver = WellKnownResolver::new(|url: &Url| {
+        requested.borrow_mut().push(url.to_string());
+        Ok(HttpResponse {
+            status: 200,
+            body: format!("dat://{}\nTTL=3600\n", KEY),
+        })
+    });
+
+    let dat_url = DatUrl::parse("dat://Example.com+c5/docs/index.html?lang=en#top")?;
+    let resolved = resolver.resolve(&dat_url)?;
+
+    assert_eq!(
+        vec!["https://example.com/.well-known/dat".to_string()],
+        requested.into_inner()
+    );
+    assert_eq!(
+        format!("dat://{}+c5/docs/index.html?lang=en#top", KEY),
+        resolved.to_string()
+    );
+    Ok(())
+}
+
+#[test]
+fn it_parses_the_ttl() -> Result<(), Box<dyn std::error::Error>> {
+    let key: DatKey = KEY.parse()?;
+    let expected = |ttl| Ok(Resolution { key, ttl });
+
+    let body = format!("dat://{}\nTTL=3600\n", KEY);
+    assert_eq!(
+        expected(Some(Duration::from_secs(3600))),
+        WellKnownResolver::new(ok(&body)).lookup("example.com")
+    );
+
+    let body = format!("\r\n  dat://{}/\r\nttl = 60\r\n", KEY);
+    assert_eq!(
+        expected(Some(Duration::from_secs(60))),
+        WellKnownResolver::new(ok(&body)).lookup("example.com")
+    );
+
+    let body = format!("hyper://{}\nTTL=soon\n", KEY);
+    assert_eq!(
+        expected(None),
+        WellKnownResolver::new(ok(&body)).lookup("example.com")
+    );
+    Ok(())
+}
+
+#[test]
+fn it_clamps_long_ttls() -> Result<(), Box<dyn std::error::Error>> {
+    let key: DatKey = KEY.parse()?;
+    assert_eq!(Duration::from_secs(2_147_483_647), MAX_TTL);
+
+    for ttl in &[u64::MAX, 2_147_483_648] {
+        let body = format!("dat://{}\nTTL={}\n", KEY, ttl);
+        assert_eq!(
+            Ok(Resolution {
+                key,
+                ttl: Some(MAX_TTL)
+            }),
+            WellKnownResolver::new(ok(&body)).lookup("example.com")
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn it_rejects_malformed_files() {
+    let bodies = [
+        String::new(),
+        "<html></html>".to_string(),
+        format!("TTL=3600\ndat://{}", KEY),
+        "dat://example.com".to_string(),
+        format!("dat://{}+5", KEY),
+        format!("https://{}", KEY),
+    ];
+    for body in &bodies {
+        assert_eq!(
+            Err(ResolveError::MalformedRecord),
+            WellKnownResolver::new(ok(body)).lookup("example.com"),
+            "{}",
+            body
+        );
+    }
+}
+
+#[test]
+fn it_reports_http_and_transport_failures() {
+    let not_found = WellKnownResolver::new(|_: &Url| {
+        Ok(HttpResponse {
+            status: 404,
+            body: String::new(),
+        })
+    });
+    assert_eq!(
+        Err(ResolveError::HttpStatus(404)),
+        not_found.lookup("example.com")
+    );
+
+    let refused =
+        WellKnownResolver::new(|_: &Url| Err(ResolveError::Transport("refused".to_string())));
+    assert_eq!(
+        Err(ResolveError::Transport("refused".to_string())),
+        refused.lookup("example.com")
+    );
+}
+
+#[test]
+fn it_skips_keys_and_ip_addresses() -> Result<(), Box<dyn std::error::Error>> {
+    let resolver = WellKnownResolver::new(|_: &Url| -> Result<HttpResponse, ResolveError> {
+        panic!("no request expected")
+    });
+
+    let dat_url = DatUrl::parse(&format!("dat://{}+5/", KEY))?.into_owned();
+    assert_eq!(dat_url, resolver.resolve(&dat_url)?);
+
+    let dat_url = DatUrl::parse("dat://192.0.2.0/")?;
+    assert_eq!(Err(ResolveError::InvalidDomain), resolver.resolve(&dat_url));
+    assert_eq!(
+        Err(ResolveError::InvalidDomain),
+        resolver.lookup("192.0.2.0")
+    );
+    Ok(())
+}
+
+/// Plain HTTP transport sending every request to a local stand-in server.
+fn local_transport(address: SocketAddr) -> impl Fn(&Url) -> Result<HttpResponse, ResolveError> {
+    move |url: &Url| {
+        let transport = |error: std::io::Error| ResolveError::Transport(error.to_string());
+        let mut stream = TcpStream::connect(address).map_err(transport)?;
+        write!(
+            stream,
+            "GET {} HTTP/1.0\r\nHost: {}\r\n\r\n",
+            url.path(),
+            url.host_str().unwrap_or_default()
+        )
+        .map_err(transport)?;
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).map_err(transport)?;
+        let (head, body) = response.split_once("\r\n\r\n").unwrap_or((&response, ""));
+        let status = head
+            .split(' ')
+            .nth(1)
+            .and_then(|status| status.parse().ok())
+            .ok_or_else(|| ResolveError::Transport("bad status line".to_string()))?;
+        Ok(HttpResponse {
+            status,
+            body: body.to_string(),
+        })
+    }
+}
+
+#[test]
+fn it_resolves_against_a_local_server() -> Result<(), Box<dyn std::error::Error>> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let address = listener.local_addr()?;
+    let server = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().expect("connection");
+        let mut reader = BufReader::new(&stream);
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).expect("request line");
+        let mut header = String::new();
+        while reader.read_line(&mut header).expect("header") > 2 {
+            header.clear();
+        }
+        let response = if request_line.starts_with("GET /.well-known/dat ") {
+            format!(
+                "HTTP/1.0 200 OK\r\nContent-Type: text/plain\r\n\r\ndat://{}\nTTL=3600\n",
+                KEY
+            )
+        } else {
+            "HTTP/1.0 404 Not Found\r\n\r\n".to_string()
+        };
+        stream.write_all(response.as_bytes()).expect("response");
+    });
+
+    let resolver = WellKnownResolver::new(local_transport(address));
+    let resolution = resolver.lookup("example.com")?;
+    server.join().expect("server thread");
+
+    assert_eq!(KEY, resolution.key.to_hex());
+    assert_eq!(Some(Duration::from_secs(3600)), resolution.ttl);
+    Ok(())
+}
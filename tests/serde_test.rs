@@ -1,6 +1,13 @@
-use parse_dat_url::DatUrl;
 use parse_dat_url::Error as ParseError;
-use serde_test::{assert_tokens, Token};
+use parse_dat_url::{DatKey, DatUrl};
+use serde_derive::{Deserialize, Serialize};
+use serde_test::{assert_de_tokens, assert_de_tokens_error, assert_tokens, Token};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Structured {
+    #[serde(with = "parse_dat_url::serde::structured")]
+    url: DatUrl<'static>,
+}
 
 #[test]
 fn it_serializes_with_serde() -> Result<(), ParseError> {
@@ -19,3 +26,128 @@ fn it_serializes_with_serde() -> Result<(), ParseError> {
     ]);
     Ok(())
 }
+
+#[test]
+fn it_serializes_keys_with_serde() -> Result<(), ParseError> {
+    let key: DatKey = "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21".parse()?;
+
+    assert_tokens(
+        &key,
+        &[Token::Str(
+            "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21",
+        )],
+    );
+    Ok(())
+}
+
+#[test]
+fn it_serializes_parts_with_serde() -> Result<(), ParseError> {
+    let structured = Structured {
+        url: DatUrl::parse("dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21+c5/path.txt?a=1#top")?
+            .into_owned(),
+    };
+
+    assert_tokens(
+        &structured,
+        &[
+            Token::Struct {
+                name: "Structured",
+                len: 1,
+            },
+            Token::Str("url"),
+            Token::Struct {
+                name: "DatUrl",
+                len: 6,
+            },
+            Token::Str("scheme"),
+            Token::Str("dat"),
+            Token::Str("host"),
+            Token::Str("584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21"),
+            Token::Str("version"),
+            Token::Some,
+            Token::Str("c5"),
+            Token::Str("path"),
+            Token::Some,
+            Token::Str("/path.txt"),
+            Token::Str("query"),
+            Token::Some,
+            Token::Str("a=1"),
+            Token::Str("fragment"),
+            Token::Some,
+            Token::Str("top"),
+            Token::StructEnd,
+            Token::StructEnd,
+        ],
+    );
+    Ok(())
+}
+
+#[test]
+fn it_deserializes_parts_with_missing_optionals() -> Result<(), ParseError> {
+    let structured = Structured {
+        url: DatUrl::parse("hyper://example.com")?.into_owned(),
+    };
+
+    assert_de_tokens(
+        &structured,
+        &[
+            Token::Struct {
+                name: "Structured",
+                len: 1,
+            },
+            Token::Str("url"),
+            Token::Map { len: Some(3) },
+            Token::Str("host"),
+            Token::Str("example.com"),
+            Token::Str("scheme"),
+            Token::Str("hyper"),
+            Token::Str("version"),
+            Token::None,
+            Token::MapEnd,
+            Token::StructEnd,
+        ],
+    );
+    Ok(())
+}
+
+#[test]
+fn it_validates_parts_like_parse() {
+    let tokens = |host: &'static str, version: &'static str| {
+        vec![
+            Token::Struct {
+                name: "Structured",
+                len: 1,
+            },
+            Token::Str("url"),
+            Token::Map { len: Some(3) },
+            Token::Str("scheme"),
+            Token::Str("dat"),
+            Token::Str("host"),
+            Token::Str(host),
+            Token::Str("version"),
+            Token::Some,
+            Token::Str(version),
+            Token::MapEnd,
+        ]
+    };
+
+    assert_de_tokens_error::<Structured>(&tokens("example.com", "01x"), "malformed version on url");
+    assert_de_tokens_error::<Structured>(
+        &tokens("example.com/path", "5"),
+        "parts do not form the dat url dat://example.com/path+5",
+    );
+    assert_de_tokens_error::<Structured>(
+        &[
+            Token::Struct {
+                name: "Structured",
+                len: 1,
+            },
+            Token::Str("url"),
+            Token::Map { len: Some(1) },
+            Token::Str("scheme"),
+            Token::Str("dat"),
+            Token::MapEnd,
+        ],
+        "missing field `host`",
+    );
+}
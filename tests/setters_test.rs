@@ -0,0 +1,132 @@
+use parse_dat_url::DatUrl;
+use parse_dat_url::Error as ParseError;
+use parse_dat_url::Version;
+use pretty_assertions::assert_eq;
+
+const KEY: &str = "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21";
+
+#[test]
+fn it_sets_and_clears_the_version() -> Result<(), ParseError> {
+    let mut dat_url = DatUrl::parse(KEY)?;
+
+    dat_url.set_version(Version::Tag("v1.0.0".into()))?;
+    assert_eq!(DatUrl::parse(&format!("{}+v1.0.0", KEY))?, dat_url);
+
+    dat_url.clear_version();
+    assert_eq!(DatUrl::parse(KEY)?, dat_url);
+    Ok(())
+}
+
+#[test]
+fn it_keeps_hyper_versioning_rules() -> Result<(), ParseError> {
+    let url = format!("hyper://{}+1/", KEY);
+    let mut dat_url = DatUrl::parse(&url)?;
+
+    assert_eq!(
+        Err(ParseError::InvalidVersion),
+        dat_url.set_version(Version::Content(2))
+    );
+    assert_eq!(DatUrl::parse(&url)?, dat_url);
+    Ok(())
+}
+
+#[test]
+fn it_rejects_versions_which_do_not_read_back() -> Result<(), ParseError> {
+    let url = "dat://example.com+1/file.txt";
+    let mut dat_url = DatUrl::parse(url)?;
+
+    for tag in &["1x", "", "a/b", "a?b", "a#b", "a+b", "latest"] {
+        assert_eq!(
+            Err(ParseError::InvalidVersion),
+            dat_url.set_version(Version::Tag((*tag).into())),
+            "{:?}",
+            tag
+        );
+        assert_eq!(DatUrl::parse(url)?, dat_url);
+    }
+    Ok(())
+}
+
+#[test]
+fn it_sets_the_path() -> Result<(), ParseError> {
+    let mut dat_url = DatUrl::parse("dat://example.com+1/file.txt?x=1#top")?;
+
+    dat_url.set_path("/path/to+file.txt");
+    assert_eq!(
+        DatUrl::parse("dat://example.com+1/path/to+file.txt?x=1#top")?,
+        dat_url
+    );
+
+    dat_url.set_path("index.html");
+    assert_eq!(
+        DatUrl::parse("dat://example.com+1/index.html?x=1#top")?,
+        dat_url
+    );
+
+    dat_url.set_path("/what?#");
+    assert_eq!(
+        DatUrl::parse("dat://example.com+1/what%3F%23?x=1#top")?,
+        dat_url
+    );
+
+    dat_url.set_path("");
+    assert_eq!(DatUrl::parse("dat://example.com+1?x=1#top")?, dat_url);
+    Ok(())
+}
+
+#[test]
+fn it_sets_the_query_and_fragment() -> Result<(), ParseError> {
+    let mut dat_url = DatUrl::parse("dat://example.com+1/file.txt")?;
+
+    dat_url.set_query(Some("page=2"));
+    dat_url.set_fragment(Some("top"));
+    assert_eq!(
+        DatUrl::parse("dat://example.com+1/file.txt?page=2#top")?,
+        dat_url
+    );
+
+    dat_url.set_query(Some("a#b"));
+    assert_eq!(
+        DatUrl::parse("dat://example.com+1/file.txt?a%23b#top")?,
+        dat_url
+    );
+
+    dat_url.set_query(None);
+    dat_url.set_fragment(None);
+    assert_eq!(DatUrl::parse("dat://example.com+1/file.txt")?, dat_url);
+    Ok(())
+}
+
+#[test]
+fn it_sets_the_host() -> Result<(), ParseError> {
+    let mut dat_url = DatUrl::parse("dat://example.com+1/file.txt?x=1")?;
+
+    dat_url.set_host(KEY)?;
+    assert_eq!(DatUrl::parse(&format!("{}+1/file.txt?x=1", KEY))?, dat_url);
+    assert!(dat_url.key().is_some());
+
+    dat_url.set_host("192.0.2.0:3282")?;
+    assert_eq!(DatUrl::parse("192.0.2.0:3282+1/file.txt?x=1")?, dat_url);
+    assert_eq!(Some(3282), dat_url.dat_host().port());
+    Ok(())
+}
+
+#[test]
+fn it_rejects_invalid_hosts() -> Result<(), ParseError> {
+    let original = DatUrl::parse("dat://example.com+1/file.txt")?;
+    let mut dat_url = original.clone();
+
+    assert_eq!(Err(ParseError::MissingHostname), dat_url.set_host(""));
+    assert_eq!(
+        Err(ParseError::InvalidUrl(
+            url::ParseError::InvalidDomainCharacter
+        )),
+        dat_url.set_host("example.com+2")
+    );
+    assert_eq!(
+        Err(ParseError::InvalidUrl(url::ParseError::InvalidIpv6Address)),
+        dat_url.set_host("[")
+    );
+    assert_eq!(original, dat_url);
+    Ok(())
+}
@@ -1,5 +1,5 @@
-use parse_dat_url::DatUrl;
 use parse_dat_url::Error as ParseError;
+use parse_dat_url::{DatUrl, Version};
 use pretty_assertions::assert_eq;
 use std::convert::TryInto;
 use url::Url;
@@ -15,7 +15,7 @@ fn it_exposes_the_fields() -> Result<(), ParseError> {
         "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21",
         dat.host()
     );
-    assert_eq!(&Some("0.0.0.1".into()), dat.version());
+    assert_eq!(&Some(Version::Tag("0.0.0.1".into())), dat.version());
     assert_eq!(&Some("/file.txt".into()), dat.path());
     Ok(())
 }
@@ -108,3 +108,22 @@ fn converts_dat_url_into_string() -> Result<(), ParseError> {
     );
     Ok(())
 }
+
+#[test]
+fn it_exposes_query_and_fragment() -> Result<(), ParseError> {
+    let url =
+        "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21+1/file.txt?x=1&y=a%20b#top";
+    let dat = DatUrl::parse(url)?;
+
+    assert_eq!(&Some("/file.txt".into()), dat.path());
+    assert_eq!(&Some("x=1&y=a%20b".into()), dat.query());
+    assert_eq!(&Some("top".into()), dat.fragment());
+    assert_eq!(
+        vec![("x".into(), "1".into()), ("y".into(), "a b".into())],
+        dat.query_pairs()
+            .into_owned()
+            .collect::<Vec<(String, String)>>()
+    );
+    assert_eq!(url, dat.to_string());
+    Ok(())
+}
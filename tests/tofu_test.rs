@@ -0,0 +1,155 @@
+use parse_dat_url::resolve::{HostsResolver, Resolution, ResolveError, Resolver, TofuResolver};
+use parse_dat_url::{DatKey, DatUrl};
+use pretty_assertions::assert_eq;
+use std::cell::Cell;
+
+const KEY: &str = "584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21";
+const OTHER_KEY: &str = "b0c5c0ec5c7e7a5f5a4a3f6a8c1ee2b0b2e93b3e5e0a6c5d7b9c3c1c0e6b7a5d";
+
+/// Resolver answering with a key which can be switched, as a hijacked domain would.
+struct Switching(Cell<DatKey>);
+
+impl Resolver for &Switching {
+    fn lookup(&self, _domain: &str) -> Result<Resolution, ResolveError> {
+        Ok(Resolution {
+            key: self.0.get(),
+            ttl: None,
+        })
+    }
+}
+
+fn keys() -> (DatKey, DatKey) {
+    (
+        KEY.parse().expect("valid key"),
+        OTHER_KEY.parse().expect("valid key"),
+    )
+}
+
+#[test]
+fn it_pins_the_first_key_seen() -> Result<(), ResolveError> {
+    let (old, new) = keys();
+    let source = Switching(Cell::new(old));
+    let tofu = TofuResolver::new(&source);
+
+    assert_eq!(None, tofu.pinned("example.com"));
+    assert_eq!(old, tofu.lookup("example.com")?.key);
+    assert_eq!(Some(old), tofu.pinned("Example.com"));
+
+    source.0.set(new);
+    assert_eq!(
+        Err(ResolveError::KeyChanged { old, new }),
+        tofu.lookup("example.com")
+    );
+    assert_eq!(new, tofu.lookup("other.example.com")?.key);
+    assert_eq!(Some(new), tofu.pinned("other.example.com"));
+    Ok(())
+}
+
+#[test]
+fn it_is_not_bypassed_by_a_fallback() -> Result<(), ResolveError> {
+    let (old, new) = keys();
+    let source = Switching(Cell::new(old));
+    let mut fallback = HostsResolver::new();
+    fallback.insert("example.com", new);
+    let resolver = TofuResolver::new(&source).or(fallback);
+
+    assert_eq!(old, resolver.lookup("example.com")?.key);
+    source.0.set(new);
+    assert_eq!(
+        Err(ResolveError::KeyChanged { old, new }),
+        resolver.lookup("example.com")
+    );
+    Ok(())
+}
+
+#[test]
+fn it_reports_changes_on_resolve() -> Result<(), Box<dyn std::error::Error>> {
+    let (old, new) = keys();
+    let source = Switching(Cell::new(new));
+    let tofu = TofuResolver::new(&source).with_pins(vec![("example.com".to_string(), old)]);
+
+    let dat_url = DatUrl::parse("dat://example.com+5/index.html")?;
+    assert_eq!(
+        Err(ResolveError::KeyChanged { old, new }),
+        tofu.resolve(&dat_url)
+    );
+    assert_eq!(
+        format!("domain key changed from {} to {}", KEY, OTHER_KEY),
+        tofu.resolve(&dat_url).unwrap_err().to_string()
+    );
+    Ok(())
+}
+
+#[test]
+fn it_accepts_changes_explicitly() -> Result<(), ResolveError> {
+    let (old, new) = keys();
+    let source = Switching(Cell::new(new));
+    let tofu = TofuResolver::new(&source).with_pins(vec![("example.com".to_string(), old)]);
+
+    match tofu.lookup("example.com") {
+        Err(ResolveError::KeyChanged { new, .. }) => tofu.accept("example.com", new),
+        other => panic!("unexpected answer {:?}", other),
+    }
+    assert_eq!(new, tofu.lookup("example.com")?.key);
+
+    assert_eq!(Some(new), tofu.forget("example.com"));
+    source.0.set(old);
+    assert_eq!(old, tofu.lookup("example.com")?.key);
+    Ok(())
+}
+
+#[test]
+fn it_saves_and_loads_pins() -> Result<(), Box<dyn std::error::Error>> {
+    let (old, new) = keys();
+    let path = std::env::temp_dir().join(format!("parse-dat-url-pins-{}", std::process::id()));
+
+    let source = Switching(Cell::new(old));
+    let tofu = TofuResolver::new(&source);
+    tofu.lookup("example.com")?;
+    tofu.lookup("www.example.com")?;
+    tofu.save(&path)?;
+
+    source.0.set(new);
+    let restarted = TofuResolver::new(&source);
+    restarted.load(&path)?;
+    std::fs::remove_file(&path)?;
+
+    assert_eq!(tofu.pins(), restarted.pins());
+    assert_eq!(
+        Err(ResolveError::KeyChanged { old, new }),
+        restarted.lookup("www.example.com")
+    );
+    restarted.load(&path)?;
+    Ok(())
+}
+
+#[test]
+fn it_saves_concurrently_without_partial_files() -> Result<(), Box<dyn std::error::Error>> {
+    let (old, _) = keys();
+    let directory = std::env::temp_dir().join(format!(
+        "parse-dat-url-pins-concurrent-{}",
+        std::process::id()
+    ));
+    std::fs::create_dir_all(&directory)?;
+    let path = directory.join("pins");
+
+    let tofu = TofuResolver::new(HostsResolver::new()).with_pins(vec![("example.com".into(), old)]);
+    std::thread::scope(|scope| {
+        for _ in 0..8 {
+            scope.spawn(|| {
+                for _ in 0..20 {
+                    tofu.save(&path).expect("concurrent saves succeed");
+                }
+            });
+        }
+    });
+
+    let files = std::fs::read_dir(&directory)?.count();
+    let restarted = TofuResolver::new(HostsResolver::new());
+    restarted.load(&path)?;
+    std::fs::remove_dir_all(&directory)?;
+
+    assert_eq!(1, files);
+    assert_eq!(tofu.pins(), restarted.pins());
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use parse_dat_url::DatUrl;
+use parse_dat_url::Error as ParseError;
+use parse_dat_url::Version;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn it_parses_each_version_kind() {
+    assert_eq!(Ok(Version::Number(0)), Version::parse("0"));
+    assert_eq!(Ok(Version::Number(42)), Version::parse("42"));
+    assert_eq!(Ok(Version::Content(1)), Version::parse("c1"));
+    assert_eq!(Ok(Version::Tag("v1.0.0".into())), Version::parse("v1.0.0"));
+    assert_eq!(
+        Ok(Version::Tag("0.0.0.1".into())),
+        Version::parse("0.0.0.1")
+    );
+    assert_eq!(Ok(Version::Tag("cool".into())), Version::parse("cool"));
+    assert_eq!(Ok(Version::Latest), Version::parse("latest"));
+    assert_eq!(Ok(Version::Preview), Version::parse("preview"));
+}
+
+#[test]
+fn it_rejects_malformed_numeric_versions() {
+    for version in &[
+        "01x",
+        "01",
+        "1x",
+        "c1x",
+        "c01",
+        "1..0",
+        "18446744073709551616",
+        "",
+    ] {
+        assert_eq!(Err(ParseError::InvalidVersion), Version::parse(version));
+    }
+}
+
+#[test]
+fn it_rejects_malformed_versions_on_urls() {
+    assert_eq!(
+        Err(ParseError::InvalidVersion),
+        DatUrl::parse(
+            "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21+01x/file.txt"
+        )
+    );
+}
+
+#[test]
+fn it_round_trips_through_display() -> Result<(), ParseError> {
+    for version in &[
+        "0", "1", "c1", "v1", "v1.0.0", "0.0.0.1", "latest", "preview",
+    ] {
+        assert_eq!(*version, Version::parse(version)?.to_string());
+
+        let url = format!(
+            "dat://584faa05d394190ab1a3f0240607f9bf2b7e2bd9968830a11cf77db0cea36a21+{}/file.txt",
+            version
+        );
+        assert_eq!(url, DatUrl::parse(&url)?.to_string());
+    }
+    Ok(())
+}
+
+#[test]
+fn it_becomes_owned() -> Result<(), ParseError> {
+    let version: Version<'static> = {
+        let tag = String::from("v1.0.0");
+        Version::parse(&tag)?.into_owned()
+    };
+
+    assert_eq!(Ok(version), "v1.0.0".parse());
+    Ok(())
+}